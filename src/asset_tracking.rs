@@ -2,13 +2,27 @@
 
 use std::collections::VecDeque;
 
-use bevy::prelude::*;
+use bevy::{
+    asset::AssetPath,
+    image::{ImageLoaderSettings, ImageSampler},
+    prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
     app.add_systems(PreUpdate, load_resource_assets);
 }
 
+/// Loads an image with `nearest` sampling, which preserves the pixel art style.
+pub fn load_pixel_art<'a>(
+    asset_server: &AssetServer,
+    path: impl Into<AssetPath<'a>>,
+) -> Handle<Image> {
+    asset_server.load_with_settings(path, |settings: &mut ImageLoaderSettings| {
+        settings.sampler = ImageSampler::nearest();
+    })
+}
+
 pub trait LoadResource {
     /// This will load the [`Resource`] as an [`Asset`]. When all of its asset dependencies
     /// have been loaded, it will be inserted as a resource. This ensures that the resource only
@@ -0,0 +1,247 @@
+pub mod music;
+pub mod pause;
+pub mod voices;
+
+use bevy::{
+    audio::{AudioSinkPlayback, SpatialAudioSink, SpatialScale, Volume},
+    ecs::component::Mutable,
+    prelude::*,
+};
+
+use crate::audio::pause::PauseDuck;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AudioCategory>()
+        .register_type::<CategoryVolumes>()
+        .register_type::<Gain>()
+        .init_resource::<CategoryVolumes>();
+
+    app.add_plugins((music::plugin, pause::plugin, voices::plugin));
+
+    app.add_observer(apply_volume_to_new_sink::<AudioSink>);
+    app.add_observer(apply_volume_to_new_sink::<SpatialAudioSink>);
+    app.add_systems(
+        Update,
+        (
+            apply_volumes::<AudioSink>,
+            apply_volumes::<SpatialAudioSink>,
+        )
+            .in_set(AudioSystems::ApplyVolumes),
+    );
+}
+
+/// How many world units make up one unit of distance for spatial audio.
+///
+/// Spatial sounds play at full volume within this distance of the listener and
+/// fall off with the square of the distance beyond it.
+pub const SPATIAL_AUDIO_UNIT: f32 = 48.0;
+
+/// The distance between the listener's ears, in world units. The larger it is,
+/// the harder sounds are panned to the side they come from.
+pub const SPATIAL_EAR_GAP: f32 = 32.0;
+
+/// Bevy's spatial audio uses the 3D distance, so flatten the z axis, which is
+/// only used for draw order.
+pub fn spatial_scale() -> SpatialScale {
+    SpatialScale::new_2d(1.0 / SPATIAL_AUDIO_UNIT)
+}
+
+/// Systems that change the volume of audio entities run before
+/// [`AudioSystems::ApplyVolumes`], so that changes are heard right away.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum AudioSystems {
+    ApplyVolumes,
+}
+
+/// Which volume setting applies to an audio entity. Required by the category
+/// markers below, so there's no need to add it by hand.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub enum AudioCategory {
+    Music,
+    SoundEffect,
+    Ui,
+    Ambient,
+}
+
+/// The volume of each [`AudioCategory`], applied on top of [`GlobalVolume`].
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct CategoryVolumes {
+    pub music: Volume,
+    pub sound_effects: Volume,
+    pub ui: Volume,
+    pub ambient: Volume,
+}
+
+impl Default for CategoryVolumes {
+    fn default() -> Self {
+        Self {
+            music: Volume::Linear(1.0),
+            sound_effects: Volume::Linear(1.0),
+            ui: Volume::Linear(1.0),
+            ambient: Volume::Linear(1.0),
+        }
+    }
+}
+
+impl CategoryVolumes {
+    pub fn get(&self, category: AudioCategory) -> Volume {
+        match category {
+            AudioCategory::Music => self.music,
+            AudioCategory::SoundEffect => self.sound_effects,
+            AudioCategory::Ui => self.ui,
+            AudioCategory::Ambient => self.ambient,
+        }
+    }
+}
+
+/// An extra linear volume factor for a single audio entity, e.g. to fade it in
+/// or out.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Gain(pub f32);
+
+impl Default for Gain {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// general "music" category (e.g. global background music, soundtrack).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+#[require(AudioCategory = AudioCategory::Music)]
+pub struct Music;
+
+/// A music audio instance.
+pub fn music(handle: Handle<AudioSource>) -> impl Bundle {
+    (AudioPlayer(handle), PlaybackSettings::LOOP, Music)
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// general "sound effect" category (e.g. footsteps, the sound of a magic spell, a door opening).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+#[require(AudioCategory = AudioCategory::SoundEffect)]
+pub struct SoundEffect;
+
+/// A sound effect that's heard from where it was spawned, relative to the
+/// [`SpatialListener`]. `playback` is usually [`PlaybackSettings::DESPAWN`],
+/// e.g. with a random speed.
+///
+/// Spawn it as a child of the entity making the sound so it moves along, or
+/// give it a [`Transform`] of its own.
+pub fn world_sound_effect(handle: Handle<AudioSource>, playback: PlaybackSettings) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        playback.with_spatial(true),
+        SoundEffect,
+        Transform::default(),
+    )
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// "UI sound" category (e.g. button clicks and hovers).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+#[require(AudioCategory = AudioCategory::Ui)]
+pub struct UiSound;
+
+/// A UI sound audio instance.
+pub fn ui_sound(handle: Handle<AudioSource>) -> impl Bundle {
+    (AudioPlayer(handle), PlaybackSettings::DESPAWN, UiSound)
+}
+
+/// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
+/// "ambient" category (e.g. waves, birdsong, wind).
+///
+/// This can then be used to query for and operate on sounds in that category.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+#[require(AudioCategory = AudioCategory::Ambient)]
+pub struct Ambient;
+
+/// An ambient audio instance that loops until despawned.
+pub fn ambient(handle: Handle<AudioSource>) -> impl Bundle {
+    (AudioPlayer(handle), PlaybackSettings::LOOP, Ambient)
+}
+
+/// The volume an audio entity should play at.
+fn sink_volume(
+    global_volume: &GlobalVolume,
+    category_volumes: &CategoryVolumes,
+    playback: &PlaybackSettings,
+    pause_duck: &PauseDuck,
+    category: Option<&AudioCategory>,
+    gain: Option<&Gain>,
+) -> Volume {
+    let category_volume = category.map_or(Volume::Linear(1.0), |&category| {
+        category_volumes.get(category)
+    });
+    let gain = Volume::Linear(gain.map_or(1.0, |gain| gain.0) * pause_duck.gain(category));
+    global_volume.volume * category_volume * playback.volume * gain
+}
+
+/// Bevy only applies [`GlobalVolume`] to new audio, so this adds the category
+/// volume as soon as playback starts.
+fn apply_volume_to_new_sink<S: AudioSinkPlayback + Component<Mutability = Mutable>>(
+    add: On<Add, S>,
+    global_volume: Res<GlobalVolume>,
+    category_volumes: Res<CategoryVolumes>,
+    pause_duck: Res<PauseDuck>,
+    mut audio_query: Query<(
+        &PlaybackSettings,
+        Option<&AudioCategory>,
+        Option<&Gain>,
+        &mut S,
+    )>,
+) {
+    if let Ok((playback, category, gain, mut sink)) = audio_query.get_mut(add.entity) {
+        sink.set_volume(sink_volume(
+            &global_volume,
+            &category_volumes,
+            playback,
+            &pause_duck,
+            category,
+            gain,
+        ));
+    }
+}
+
+/// [`GlobalVolume`] doesn't apply to already-running audio entities, so this system will update them
+/// whenever a volume changes.
+fn apply_volumes<S: AudioSinkPlayback + Component<Mutability = Mutable>>(
+    global_volume: Res<GlobalVolume>,
+    category_volumes: Res<CategoryVolumes>,
+    pause_duck: Res<PauseDuck>,
+    mut audio_query: Query<(
+        &PlaybackSettings,
+        Option<&AudioCategory>,
+        Option<Ref<Gain>>,
+        &mut S,
+    )>,
+) {
+    let volumes_changed =
+        global_volume.is_changed() || category_volumes.is_changed() || pause_duck.is_changed();
+    for (playback, category, gain, mut sink) in &mut audio_query {
+        if !volumes_changed && !gain.as_ref().is_some_and(Ref::is_changed) {
+            continue;
+        }
+        sink.set_volume(sink_volume(
+            &global_volume,
+            &category_volumes,
+            playback,
+            &pause_duck,
+            category,
+            gain.as_deref(),
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+//! The music director, which owns whatever music is playing.
+//!
+//! Ask the [`MusicDirector`] to [`play`](MusicDirector::play) a track instead
+//! of spawning music directly. It crossfades from the previous track, and
+//! tracks that fade out are paused rather than stopped, so they resume where
+//! they left off when they're played again.
+//!
+//! Music ducks while the game is paused, see [`pause`](super::pause).
+
+use bevy::prelude::*;
+
+use crate::audio::{AudioSystems, Gain, music};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<MusicTrack>()
+        .init_resource::<MusicDirector>();
+
+    app.add_systems(
+        Update,
+        (start_music_tracks, fade_music_tracks)
+            .chain()
+            .before(AudioSystems::ApplyVolumes),
+    );
+}
+
+/// How long it takes to crossfade from one track to another, in seconds.
+const CROSSFADE_SECS: f32 = 1.5;
+
+/// Decides which music track is playing.
+#[derive(Resource, Default, Debug)]
+pub struct MusicDirector {
+    current: Option<Handle<AudioSource>>,
+}
+
+impl MusicDirector {
+    /// Crossfades to `track`, resuming it if it was played before.
+    pub fn play(&mut self, track: Handle<AudioSource>) {
+        self.current = Some(track);
+    }
+
+    /// Fades out the current track.
+    pub fn stop(&mut self) {
+        self.current = None;
+    }
+
+    pub fn current(&self) -> Option<&Handle<AudioSource>> {
+        self.current.as_ref()
+    }
+}
+
+/// A track the director has played.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+struct MusicTrack {
+    track: Handle<AudioSource>,
+    /// How far the track has faded in, from 0 (silent) to 1.
+    fade: f32,
+}
+
+fn start_music_tracks(
+    mut commands: Commands,
+    director: Res<MusicDirector>,
+    tracks: Query<&MusicTrack>,
+) {
+    let Some(current) = director.current() else {
+        return;
+    };
+    if tracks.iter().any(|track| track.track == *current) {
+        return;
+    }
+    commands.spawn((
+        Name::new("Music Track"),
+        MusicTrack {
+            track: current.clone(),
+            fade: 0.0,
+        },
+        music(current.clone()),
+        Gain(0.0),
+    ));
+}
+
+fn fade_music_tracks(
+    time: Res<Time>,
+    director: Res<MusicDirector>,
+    mut tracks: Query<(&mut MusicTrack, &mut Gain, Option<&AudioSink>)>,
+) {
+    for (mut track, mut gain, sink) in &mut tracks {
+        let fade = if director.current() == Some(&track.track) {
+            1.0
+        } else {
+            0.0
+        };
+        track.fade = move_towards(track.fade, fade, time.delta_secs() / CROSSFADE_SECS);
+        gain.set_if_neq(Gain(track.fade));
+
+        // Keep silent tracks paused, so they resume where they left off.
+        if let Some(sink) = sink {
+            if track.fade == 0.0 && !sink.is_paused() {
+                sink.pause();
+            } else if track.fade > 0.0 && sink.is_paused() {
+                sink.play();
+            }
+        }
+    }
+}
+
+fn move_towards(current: f32, target: f32, max_delta: f32) -> f32 {
+    current + (target - current).clamp(-max_delta, max_delta)
+}
@@ -0,0 +1,136 @@
+//! What audio does while the game is paused, which includes conversations.
+//!
+//! Each [`AudioCategory`] follows a [`PausePolicy`]: music and ambience duck,
+//! sound effects in the world pause in place, and UI sounds play as usual.
+//! Everything is restored when the game is unpaused.
+
+use bevy::{
+    audio::{AudioSinkPlayback, SpatialAudioSink},
+    prelude::*,
+};
+
+use crate::{
+    Pause,
+    audio::{AudioCategory, AudioSystems},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<PauseDuck>()
+        .register_type::<PausedByGame>()
+        .init_resource::<PauseDuck>();
+
+    app.add_observer(pause_new_sink::<AudioSink>);
+    app.add_observer(pause_new_sink::<SpatialAudioSink>);
+    app.add_systems(
+        OnEnter(Pause(true)),
+        (pause_sinks::<AudioSink>, pause_sinks::<SpatialAudioSink>),
+    );
+    app.add_systems(
+        OnExit(Pause(true)),
+        (resume_sinks::<AudioSink>, resume_sinks::<SpatialAudioSink>),
+    );
+    app.add_systems(Update, duck_while_paused.before(AudioSystems::ApplyVolumes));
+}
+
+/// How loud ducked audio is, relative to its normal volume.
+const DUCKED_GAIN: f32 = 0.35;
+
+/// How long it takes to duck audio or bring it back up, in seconds.
+const DUCK_SECS: f32 = 0.3;
+
+/// How a category of audio behaves while the game is paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PausePolicy {
+    /// Keep playing as usual.
+    Play,
+    /// Keep playing, but quieter.
+    Duck,
+    /// Stop in place, and continue where it left off when unpaused.
+    Pause,
+}
+
+impl PausePolicy {
+    fn of(category: AudioCategory) -> Self {
+        match category {
+            AudioCategory::Music | AudioCategory::Ambient => Self::Duck,
+            AudioCategory::SoundEffect => Self::Pause,
+            AudioCategory::Ui => Self::Play,
+        }
+    }
+}
+
+/// How loud audio that ducks while paused currently is, from [`DUCKED_GAIN`]
+/// to 1.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct PauseDuck(f32);
+
+impl Default for PauseDuck {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl PauseDuck {
+    /// The gain for audio in `category`.
+    pub(super) fn gain(&self, category: Option<&AudioCategory>) -> f32 {
+        match category.map(|&category| PausePolicy::of(category)) {
+            Some(PausePolicy::Duck) => self.0,
+            _ => 1.0,
+        }
+    }
+}
+
+/// A sink that was paused because the game was, and resumes with it.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct PausedByGame;
+
+fn duck_while_paused(time: Res<Time>, pause: Res<State<Pause>>, mut duck: ResMut<PauseDuck>) {
+    let target = if pause.get().0 { DUCKED_GAIN } else { 1.0 };
+    let max_delta = time.delta_secs() * (1.0 - DUCKED_GAIN) / DUCK_SECS;
+    duck.set_if_neq(PauseDuck(
+        duck.0 + (target - duck.0).clamp(-max_delta, max_delta),
+    ));
+}
+
+fn pause_sinks<S: AudioSinkPlayback + Component>(
+    mut commands: Commands,
+    sinks: Query<(Entity, &AudioCategory, &S)>,
+) {
+    for (entity, &category, sink) in &sinks {
+        if PausePolicy::of(category) == PausePolicy::Pause && !sink.is_paused() {
+            sink.pause();
+            commands.entity(entity).insert(PausedByGame);
+        }
+    }
+}
+
+fn resume_sinks<S: AudioSinkPlayback + Component>(
+    mut commands: Commands,
+    sinks: Query<(Entity, &S), With<PausedByGame>>,
+) {
+    for (entity, sink) in &sinks {
+        sink.play();
+        commands.entity(entity).remove::<PausedByGame>();
+    }
+}
+
+/// Sounds that start while the game is paused wait for it to be unpaused.
+fn pause_new_sink<S: AudioSinkPlayback + Component>(
+    add: On<Add, S>,
+    mut commands: Commands,
+    pause: Res<State<Pause>>,
+    sinks: Query<(&AudioCategory, &S)>,
+) {
+    if !pause.get().0 {
+        return;
+    }
+    let Ok((&category, sink)) = sinks.get(add.entity) else {
+        return;
+    };
+    if PausePolicy::of(category) == PausePolicy::Pause {
+        sink.pause();
+        commands.entity(add.entity).insert(PausedByGame);
+    }
+}
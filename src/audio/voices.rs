@@ -0,0 +1,174 @@
+//! Limits on how many sounds play at once.
+//!
+//! Every sound with an [`AudioCategory`] other than music is a voice. When a
+//! new voice would go over the [`VoiceLimits`] for its sound or its category,
+//! it steals the lowest-[priority](SoundPriority), oldest voice that isn't more
+//! important than itself, or is dropped if there is none. A one-shot sound that
+//! already started within [`VoiceLimits::repeat_window`] isn't started again,
+//! e.g. when the pointer sweeps across a row of buttons.
+
+use std::{collections::HashMap, time::Duration};
+
+use bevy::{audio::PlaybackMode, prelude::*};
+
+use crate::audio::AudioCategory;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<VoiceLimits>()
+        .register_type::<SoundPriority>()
+        .register_type::<Voice>()
+        .init_resource::<VoiceLimits>()
+        .init_resource::<RecentSounds>();
+
+    app.add_observer(limit_new_voice);
+}
+
+/// How many voices may play at once, and how often a sound may restart.
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct VoiceLimits {
+    /// How many instances of the same sound may play at once.
+    pub per_sound: usize,
+    pub sound_effects: usize,
+    pub ui: usize,
+    pub ambient: usize,
+    /// How long after a sound started it's ignored when started again.
+    pub repeat_window: Duration,
+}
+
+impl Default for VoiceLimits {
+    fn default() -> Self {
+        Self {
+            per_sound: 4,
+            sound_effects: 16,
+            ui: 4,
+            ambient: 8,
+            repeat_window: Duration::from_millis(80),
+        }
+    }
+}
+
+impl VoiceLimits {
+    /// The most voices `category` may play at once, if it is limited at all.
+    fn per_category(&self, category: AudioCategory) -> Option<usize> {
+        match category {
+            // Music is managed by the music director.
+            AudioCategory::Music => None,
+            AudioCategory::SoundEffect => Some(self.sound_effects),
+            AudioCategory::Ui => Some(self.ui),
+            AudioCategory::Ambient => Some(self.ambient),
+        }
+    }
+}
+
+/// How important a sound is to keep playing when there are too many. Sounds
+/// without one get the default priority of their [`AudioCategory`].
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[reflect(Component)]
+pub struct SoundPriority(pub u8);
+
+impl SoundPriority {
+    pub const LOW: Self = Self(50);
+    pub const NORMAL: Self = Self(100);
+    pub const HIGH: Self = Self(200);
+
+    fn of(category: AudioCategory) -> Self {
+        match category {
+            AudioCategory::Music | AudioCategory::Ui => Self::HIGH,
+            AudioCategory::SoundEffect | AudioCategory::Ambient => Self::NORMAL,
+        }
+    }
+}
+
+/// A sound that counts towards the [`VoiceLimits`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Voice {
+    priority: SoundPriority,
+    /// When the voice started, in real time.
+    started: Duration,
+}
+
+/// When each sound last started, in real time.
+#[derive(Resource, Default)]
+struct RecentSounds(HashMap<AssetId<AudioSource>, Duration>);
+
+fn limit_new_voice(
+    add: On<Add, AudioPlayer>,
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    limits: Res<VoiceLimits>,
+    mut recent_sounds: ResMut<RecentSounds>,
+    new_voices: Query<(
+        &AudioPlayer,
+        &PlaybackSettings,
+        &AudioCategory,
+        Option<&SoundPriority>,
+    )>,
+    voices: Query<(Entity, &AudioPlayer, &AudioCategory, &Voice)>,
+) {
+    let Ok((player, playback, &category, priority)) = new_voices.get(add.entity) else {
+        return;
+    };
+    let Some(category_limit) = limits.per_category(category) else {
+        return;
+    };
+    let sound = player.0.id();
+    let now = time.elapsed();
+    // Loops, e.g. of several areas with the same ambience, may start together.
+    let one_shot = !matches!(playback.mode, PlaybackMode::Loop);
+    if one_shot
+        && recent_sounds
+            .0
+            .get(&sound)
+            .is_some_and(|&started| now.saturating_sub(started) < limits.repeat_window)
+    {
+        commands.entity(add.entity).despawn();
+        return;
+    }
+
+    let priority = priority.copied().unwrap_or(SoundPriority::of(category));
+    let same_sound: Vec<_> = voices
+        .iter()
+        .filter(|(_, other, ..)| other.0.id() == sound)
+        .map(|(entity, .., voice)| (entity, voice))
+        .collect();
+    let mut stolen = None;
+    if same_sound.len() >= limits.per_sound {
+        let Some(victim) = voice_to_steal(&same_sound, priority) else {
+            commands.entity(add.entity).despawn();
+            return;
+        };
+        stolen = Some(victim);
+    }
+    let same_category: Vec<_> = voices
+        .iter()
+        .filter(|&(entity, _, &other, _)| other == category && Some(entity) != stolen)
+        .map(|(entity, .., voice)| (entity, voice))
+        .collect();
+    if same_category.len() >= category_limit {
+        let Some(victim) = voice_to_steal(&same_category, priority) else {
+            commands.entity(add.entity).despawn();
+            return;
+        };
+        commands.entity(victim).despawn();
+    }
+    if let Some(victim) = stolen {
+        commands.entity(victim).despawn();
+    }
+
+    recent_sounds.0.insert(sound, now);
+    commands.entity(add.entity).insert(Voice {
+        priority,
+        started: now,
+    });
+}
+
+/// The least important, oldest voice that a voice of `priority` may replace.
+fn voice_to_steal(voices: &[(Entity, &Voice)], priority: SoundPriority) -> Option<Entity> {
+    voices
+        .iter()
+        .filter(|(_, voice)| voice.priority <= priority)
+        .min_by_key(|(_, voice)| (voice.priority, voice.started))
+        .map(|&(entity, _)| entity)
+}
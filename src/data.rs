@@ -0,0 +1,57 @@
+//! Game data authored as [RON](https://github.com/ron-rs/ron) files in
+//! `assets/data`, such as fish tables.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+
+pub trait LoadRonAsset {
+    /// Registers `T` as an [`Asset`] that is deserialized from RON files
+    /// ending in `.{extension}`, e.g. `fish.ron`.
+    fn init_ron_asset<T: Asset + DeserializeOwned>(&mut self, extension: &'static str)
+    -> &mut Self;
+}
+
+impl LoadRonAsset for App {
+    fn init_ron_asset<T: Asset + DeserializeOwned>(
+        &mut self,
+        extension: &'static str,
+    ) -> &mut Self {
+        self.init_asset::<T>()
+            .register_asset_loader(RonAssetLoader::<T> {
+                extensions: vec![extension],
+                _marker: PhantomData,
+            })
+    }
+}
+
+#[derive(TypePath)]
+struct RonAssetLoader<T> {
+    extensions: Vec<&'static str>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for RonAssetLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+}
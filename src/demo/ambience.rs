@@ -0,0 +1,135 @@
+//! Ambient soundscapes, such as waves by the sea or drips in a cave.
+//!
+//! Give a rectangle object in Tiled an [`AmbientZone`] property to loop a sound
+//! inside it. The loop fades in as the player approaches the area and out as
+//! they leave it, and can be louder during the day than at night, e.g. for
+//! birdsong. Zones with the same track share a single loop that plays as loud
+//! as the loudest of them, so an area can be made up of several rectangles.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use crate::{
+    AppSystems,
+    audio::{AudioSystems, Gain, ambient},
+    demo::{clock::GameClock, player::Player},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AmbientZone>();
+
+    app.add_systems(
+        Update,
+        blend_ambient_loops
+            .in_set(AppSystems::Update)
+            .before(AudioSystems::ApplyVolumes)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How long an ambient loop takes to fade all the way in or out, in seconds,
+/// e.g. after teleporting.
+const FADE_SECS: f32 = 1.0;
+
+/// An area with an ambient loop, set as a property on a Tiled rectangle.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct AmbientZone {
+    /// The asset path of the looping sound.
+    track: String,
+    /// How far outside the area the loop can still be heard, in pixels.
+    fade_distance: f32,
+    /// The loop's volume during the day.
+    day_volume: f32,
+    /// The loop's volume at night.
+    night_volume: f32,
+}
+
+impl Default for AmbientZone {
+    fn default() -> Self {
+        Self {
+            track: String::new(),
+            fade_distance: 48.0,
+            day_volume: 1.0,
+            night_volume: 1.0,
+        }
+    }
+}
+
+impl AmbientZone {
+    /// How loud the loop should be for a player at `player_pos`, given the
+    /// zone's `area` and how light it is.
+    fn volume(&self, area: Rect, player_pos: Vec2, daylight: f32) -> f32 {
+        let distance = player_pos.distance(player_pos.clamp(area.min, area.max));
+        let proximity = (1.0 - distance / self.fade_distance.max(f32::EPSILON)).clamp(0.0, 1.0);
+        proximity * (self.night_volume + (self.day_volume - self.night_volume) * daylight)
+    }
+}
+
+/// The ambient loop shared by all [`AmbientZone`]s with the same track.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AmbientLoop {
+    /// The asset path of the looping sound.
+    track: String,
+}
+
+fn blend_ambient_loops(
+    mut commands: Commands,
+    time: Res<Time>,
+    clock: Res<GameClock>,
+    player: Single<&GlobalTransform, With<Player>>,
+    zones: Query<(&AmbientZone, &TiledObject, &GlobalTransform)>,
+    mut loops: Query<(Entity, &AmbientLoop, &mut Gain, Option<&AudioSink>)>,
+    asset_server: Res<AssetServer>,
+) {
+    let player_pos = player.translation().truncate();
+    let daylight = clock.daylight();
+    let mut volumes = HashMap::<&str, f32>::new();
+    for (zone, object, transform) in &zones {
+        let TiledObject::Rectangle { width, height } = *object else {
+            continue;
+        };
+        // Tiled rectangles hang down and to the right of their position.
+        let corner = transform.translation().truncate();
+        let area = Rect::from_corners(corner, corner + Vec2::new(width, -height));
+        let volume = volumes.entry(zone.track.as_str()).or_default();
+        *volume = volume.max(zone.volume(area, player_pos, daylight));
+    }
+
+    let max_delta = time.delta_secs() / FADE_SECS;
+    for (entity, ambient_loop, mut gain, sink) in &mut loops {
+        let Some(volume) = volumes.remove(ambient_loop.track.as_str()) else {
+            // None of the track's zones are left, e.g. after their map unloaded.
+            commands.entity(entity).despawn();
+            continue;
+        };
+        gain.set_if_neq(Gain(
+            gain.0 + (volume - gain.0).clamp(-max_delta, max_delta),
+        ));
+
+        // Keep loops that can't be heard paused.
+        if let Some(sink) = sink {
+            if gain.0 == 0.0 && !sink.is_paused() {
+                sink.pause();
+            } else if gain.0 > 0.0 && sink.is_paused() {
+                sink.play();
+            }
+        }
+    }
+
+    for track in volumes.into_keys() {
+        commands.spawn((
+            Name::new("Ambient Loop"),
+            AmbientLoop {
+                track: track.to_owned(),
+            },
+            ambient(asset_server.load(track)),
+            Gain(0.0),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+}
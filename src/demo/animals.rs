@@ -0,0 +1,349 @@
+//! Farm animals: cows, sheep, pigs, chickens and ducks.
+//!
+//! Give a Tiled object an [`AnimalKind`] property to spawn an animal there.
+//! Animals wander around inside the [`Pen`] they're placed in, show their
+//! affection when petted, and some of them leave behind
+//! milk or eggs once a day, which can be collected into the inventory.
+
+use std::{collections::HashMap, f32::consts::TAU, time::Duration};
+
+use avian2d::prelude::{Collider, LinearVelocity, LockedAxes, RigidBody};
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::{LoadResource, load_pixel_art},
+    demo::{
+        animation::{
+            CharacterAnimation, CharacterAnimationClip, CharacterAnimationState, CharacterBody,
+            CharacterClips,
+        },
+        behavior::Behavior,
+        clock::DayStarted,
+        emote::{Emote, EmoteKind},
+        interaction::{Interactable, Interacted},
+        inventory::{Inventory, ItemAssets, ItemCatalog},
+        movement::MovementController,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AnimalKind>()
+        .register_type::<Animal>()
+        .register_type::<Pen>()
+        .register_type::<Produce>()
+        .register_type::<ProducedBy>()
+        .register_type::<Produced>();
+    app.load_resource::<AnimalAssets>();
+    app.add_observer(pet_animal);
+    app.add_observer(lay_produce);
+    app.add_observer(collect_produce);
+
+    app.add_systems(
+        Update,
+        (spawn_animals, confine_to_pens.in_set(AppSystems::Update)).in_set(PausableSystems),
+    );
+}
+
+/// How close the player needs to be to an animal to pet it.
+const PET_RANGE: f32 = 20.0;
+
+/// How far away from an animal its produce lands.
+const PRODUCE_SCATTER: f32 = 10.0;
+
+/// How close the player needs to be to produce to collect it.
+const COLLECT_RANGE: f32 = 20.0;
+
+/// How much produce an animal leaves lying around before it stops producing
+/// until some is collected.
+const MAX_UNCOLLECTED_PRODUCE: usize = 3;
+
+/// What kind of animal a Tiled object should spawn as.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component, Default)]
+pub enum AnimalKind {
+    #[default]
+    Cow,
+    Sheep,
+    Pig,
+    Chicken,
+    Duck,
+}
+
+impl AnimalKind {
+    fn max_speed(self) -> f32 {
+        match self {
+            Self::Cow | Self::Pig => 16.0,
+            Self::Sheep => 20.0,
+            Self::Chicken | Self::Duck => 28.0,
+        }
+    }
+
+    /// What the animal leaves behind once a day, if anything.
+    fn produce(self) -> Option<ProduceKind> {
+        match self {
+            Self::Cow => Some(ProduceKind::Milk),
+            Self::Chicken | Self::Duck => Some(ProduceKind::Egg),
+            Self::Sheep | Self::Pig => None,
+        }
+    }
+}
+
+/// Marks an [`AnimalKind`] object that has been turned into an animal.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Animal {
+    /// Whether the animal was petted since it last produced something.
+    petted_today: bool,
+}
+
+/// A rectangle in Tiled that keeps the animals placed inside it from
+/// wandering off.
+#[derive(Component, Default, Debug, Reflect, PartialEq, Eq, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct Pen;
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProduceKind {
+    Milk,
+    Egg,
+}
+
+impl ProduceKind {
+    /// The item id of the produce.
+    pub fn item(self) -> &'static str {
+        match self {
+            Self::Milk => "milk",
+            Self::Egg => "egg",
+        }
+    }
+}
+
+/// Something an animal left behind, waiting to be collected into the
+/// inventory.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Interactable = Interactable::new("Collect", COLLECT_RANGE))]
+pub struct Produce(pub ProduceKind);
+
+/// The animal that left this produce behind.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+#[relationship(relationship_target = Produced)]
+pub struct ProducedBy(pub Entity);
+
+/// The produce this animal left behind that hasn't been collected yet.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+#[relationship_target(relationship = ProducedBy)]
+pub struct Produced(Vec<Entity>);
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct AnimalAssets {
+    clips: HashMap<AnimalKind, CharacterAnimationClip>,
+    #[dependency]
+    milk: Handle<Image>,
+    #[dependency]
+    egg: Handle<Image>,
+}
+
+impl FromWorld for AnimalAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        let clip = |path, size| CharacterAnimationClip {
+            // These are idle loops rather than walk cycles, so take it slow.
+            duration: Duration::from_millis(200),
+            ..CharacterAnimationClip::single(assets, path, 4, size, size)
+        };
+        let image = |path| -> Handle<Image> { load_pixel_art(assets, path) };
+        Self {
+            clips: HashMap::from_iter([
+                (
+                    AnimalKind::Cow,
+                    clip("Elements/Animals/spr_deco_cow_strip4.png", 32),
+                ),
+                (
+                    AnimalKind::Sheep,
+                    clip("Elements/Animals/spr_deco_sheep_01_strip4.png", 32),
+                ),
+                (
+                    AnimalKind::Pig,
+                    clip("Elements/Animals/spr_deco_pig_01_strip4.png", 32),
+                ),
+                (
+                    AnimalKind::Chicken,
+                    clip("Elements/Animals/spr_deco_chicken_01_strip4.png", 32),
+                ),
+                (
+                    AnimalKind::Duck,
+                    clip("Elements/Animals/spr_deco_duck_01_strip4.png", 16),
+                ),
+            ]),
+            milk: image("Elements/Crops/milk.png"),
+            egg: image("Elements/Crops/egg.png"),
+        }
+    }
+}
+
+/// The components that turn an [`AnimalKind`] object into an animal.
+fn animal(
+    kind: AnimalKind,
+    animal_assets: &AnimalAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+) -> impl Bundle {
+    let clip = &animal_assets.clips[&kind];
+    // Animals don't have separate walk cycles, so they use the same strip for
+    // everything.
+    let clips = CharacterClips(HashMap::from_iter([
+        (CharacterAnimationState::Idling, clip.clone()),
+        (CharacterAnimationState::Walking, clip.clone()),
+    ]));
+    let texture_atlas_layout = texture_atlas_layouts.add(clips.atlas_layout());
+    let animation = CharacterAnimation::new(clip);
+    (
+        Animal::default(),
+        Visibility::default(),
+        children![(
+            Name::new("Animal Body"),
+            CharacterBody,
+            Sprite::from_atlas_image(
+                clip.base_image.clone(),
+                TextureAtlas {
+                    layout: texture_atlas_layout,
+                    index: animation.frame,
+                },
+            ),
+        )],
+        RigidBody::Dynamic,
+        LinearVelocity::default(),
+        Collider::circle(4.0),
+        LockedAxes::ROTATION_LOCKED,
+        MovementController {
+            max_speed: kind.max_speed(),
+            ..default()
+        },
+        Behavior {
+            wander_radius: 24.0,
+            ..default()
+        },
+        Interactable::new("Pet", PET_RANGE),
+        clips,
+        animation,
+    )
+}
+
+fn spawn_animals(
+    mut commands: Commands,
+    spawners: Query<(Entity, &AnimalKind), Without<Animal>>,
+    animal_assets: If<Res<AnimalAssets>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    for (entity, &kind) in &spawners {
+        commands
+            .entity(entity)
+            .insert(animal(kind, &animal_assets, &mut texture_atlas_layouts));
+    }
+}
+
+/// Keeps animals inside the pen their home is in.
+fn confine_to_pens(
+    mut animals: Query<&mut Behavior, With<Animal>>,
+    pens: Query<(&TiledObject, &GlobalTransform), With<Pen>>,
+) {
+    for mut behavior in &mut animals {
+        let Some(home) = behavior.home.filter(|_| behavior.wander_area.is_none()) else {
+            continue;
+        };
+        behavior.wander_area = pens.iter().find_map(|(object, transform)| {
+            let TiledObject::Rectangle { width, height } = *object else {
+                return None;
+            };
+            // Tiled rectangles hang down and to the right of their position.
+            let corner = transform.translation().truncate();
+            let area = Rect::from_corners(corner, corner + Vec2::new(width, -height));
+            area.contains(home).then_some(area)
+        });
+    }
+}
+
+fn pet_animal(interacted: On<Interacted>, mut commands: Commands, mut animals: Query<&mut Animal>) {
+    let entity = interacted.entity;
+    let Ok(mut animal) = animals.get_mut(entity) else {
+        return;
+    };
+
+    // The first pet of the day gets the most love.
+    let kind = if animal.petted_today {
+        EmoteKind::Happy
+    } else {
+        animal.petted_today = true;
+        EmoteKind::Love
+    };
+    commands.trigger(Emote::new(entity, kind));
+}
+
+/// Starts a new day for every animal, leaving the day's produce next to those
+/// that have any, unless too much of it is still lying around.
+fn lay_produce(
+    _: On<DayStarted>,
+    mut commands: Commands,
+    mut animals: Query<(
+        Entity,
+        &mut Animal,
+        &AnimalKind,
+        &GlobalTransform,
+        Option<&Produced>,
+    )>,
+    animal_assets: If<Res<AnimalAssets>>,
+) {
+    for (entity, mut animal, kind, transform, produced) in &mut animals {
+        animal.petted_today = false;
+        let Some(produce) = kind.produce() else {
+            continue;
+        };
+        if produced.is_some_and(|produced| produced.0.len() >= MAX_UNCOLLECTED_PRODUCE) {
+            continue;
+        }
+
+        let image = match produce {
+            ProduceKind::Milk => animal_assets.milk.clone(),
+            ProduceKind::Egg => animal_assets.egg.clone(),
+        };
+        let offset = Vec2::from_angle(rand::rng().random_range(0.0..TAU)) * PRODUCE_SCATTER;
+        commands.spawn((
+            Name::new("Produce"),
+            Produce(produce),
+            ProducedBy(entity),
+            Sprite::from_image(image),
+            Transform::from_translation(transform.translation() + offset.extend(0.0)),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+}
+
+/// Puts produce into the collector's inventory, if there is room for it.
+fn collect_produce(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    produce: Query<&Produce>,
+    mut inventories: Query<&mut Inventory>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+) {
+    let (Ok(produce), Ok(mut inventory), Some(catalog)) = (
+        produce.get(interacted.entity),
+        inventories.get_mut(interacted.interactor),
+        catalogs.get(&item_assets.catalog),
+    ) else {
+        return;
+    };
+    if inventory.add(catalog, produce.0.item(), 1) > 0 {
+        info!("No room for {}", produce.0.item());
+        return;
+    }
+    commands.entity(interacted.entity).despawn();
+}
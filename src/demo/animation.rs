@@ -1,27 +1,27 @@
-//! Player sprite animation.
+//! Character sprite animation, shared by the player and NPCs.
 //! This is based on multiple examples and may be very different for your game.
 //! - [Sprite flipping](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_flipping.rs)
 //! - [Sprite animation](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_animation.rs)
 //! - [Timers](https://github.com/bevyengine/bevy/blob/latest/examples/time/timers.rs)
 
-use bevy::{
-    image::{ImageLoaderSettings, ImageSampler},
-    prelude::*,
-};
-use rand::prelude::*;
-use std::time::Duration;
+use bevy::prelude::*;
+use std::{collections::HashMap, time::Duration};
 
 use crate::{
     AppSystems, PausableSystems,
-    audio::sound_effect,
+    asset_tracking::load_pixel_art,
     demo::{
+        carry::Carrying,
+        combat::Attacking,
+        farming::Working,
+        fishing::Fishing,
+        health::{Dead, Hurt},
         movement::MovementController,
-        player::{PlayerAssets, PlayerBody, PlayerHair},
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
-    // Animate and play sound effects based on controls.
+    // Animate based on controls.
     app.add_systems(
         Update,
         (
@@ -30,7 +30,7 @@ pub(super) fn plugin(app: &mut App) {
                 update_animation_movement,
                 update_body_animation,
                 update_hair_animation,
-                trigger_step_sound_effect,
+                update_tool_animation,
             )
                 .chain()
                 .in_set(AppSystems::Update),
@@ -40,36 +40,59 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 /// Update the animation timer.
-fn update_animation_timer(time: Res<Time>, mut query: Query<&mut PlayerAnimation>) {
+fn update_animation_timer(time: Res<Time>, mut query: Query<&mut CharacterAnimation>) {
     for mut animation in &mut query {
         animation.update_timer(time.delta());
     }
 }
 
-/// Update the sprite direction and animation state (idling/walking).
+/// Update the sprite direction and animation state (idling/walking, with or
+/// without carrying something). Dying, getting hurt, attacking and activities
+/// such as farming and fishing take precedence over movement, in that order.
 fn update_animation_movement(
-    player_assets: If<Res<PlayerAssets>>,
-    mut player_query: Query<(&MovementController, &mut PlayerAnimation)>,
+    mut character_query: Query<(
+        &MovementController,
+        &CharacterClips,
+        Has<Dead>,
+        Has<Hurt>,
+        Has<Attacking>,
+        Has<Carrying>,
+        Option<&Working>,
+        Option<&Fishing>,
+        &mut CharacterAnimation,
+    )>,
 ) {
-    for (controller, mut animation) in &mut player_query {
-        let animation_state = if controller.intent == Vec2::ZERO {
-            PlayerAnimationState::Idling
+    for (controller, clips, dead, hurt, attacking, carrying, working, fishing, mut animation) in
+        &mut character_query
+    {
+        let animation_state = if dead {
+            CharacterAnimationState::Dying
+        } else if hurt {
+            CharacterAnimationState::Hurt
+        } else if attacking {
+            CharacterAnimationState::Attacking
+        } else if let Some(working) = working {
+            working.animation_state()
+        } else if let Some(fishing) = fishing {
+            fishing.animation_state()
         } else {
-            PlayerAnimationState::Walking
+            match (controller.intent == Vec2::ZERO, carrying) {
+                (true, false) => CharacterAnimationState::Idling,
+                (false, false) => CharacterAnimationState::Walking,
+                (true, true) => CharacterAnimationState::CarryIdling,
+                (false, true) => CharacterAnimationState::CarryWalking,
+            }
         };
 
-        animation.update_state(
-            animation_state,
-            player_assets.as_ref().actions.get(&animation_state),
-        );
+        animation.update_state(animation_state, clips.0.get(&animation_state));
     }
 }
 
 fn update_body_animation(
-    mut players: Query<(&MovementController, &Children, &mut PlayerAnimation)>,
-    mut body_sprites: Query<&mut Sprite, With<PlayerBody>>,
+    mut characters: Query<(&MovementController, &Children, &mut CharacterAnimation)>,
+    mut body_sprites: Query<&mut Sprite, With<CharacterBody>>,
 ) {
-    for (controller, children, animation) in &mut players {
+    for (controller, children, animation) in &mut characters {
         for &child in children {
             if let Ok(mut sprite) = body_sprites.get_mut(child) {
                 let dx = controller.intent.x;
@@ -91,10 +114,13 @@ fn update_body_animation(
 }
 
 fn update_hair_animation(
-    mut players: Query<(&MovementController, &Children, &mut PlayerAnimation)>,
-    mut hair_sprites: Query<&mut Sprite, With<PlayerHair>>,
+    mut characters: Query<(&MovementController, &Children, &mut CharacterAnimation)>,
+    mut hair_sprites: Query<&mut Sprite, With<CharacterHair>>,
 ) {
-    for (controller, children, animation) in &mut players {
+    for (controller, children, animation) in &mut characters {
+        let Some(hair_image) = &animation.clip.hair_image else {
+            continue;
+        };
         for &child in children {
             if let Ok(mut sprite) = hair_sprites.get_mut(child) {
                 let dx = controller.intent.x;
@@ -102,7 +128,7 @@ fn update_hair_animation(
                     sprite.flip_x = dx < 0.0;
                 }
 
-                sprite.image = animation.clip.hair_image.clone();
+                sprite.image = hair_image.clone();
 
                 let Some(atlas) = sprite.texture_atlas.as_mut() else {
                     continue;
@@ -115,37 +141,56 @@ fn update_hair_animation(
     }
 }
 
-/// If the player is moving, play a step sound effect synchronized with the
-/// animation.
-fn trigger_step_sound_effect(
-    mut commands: Commands,
-    player_assets: If<Res<PlayerAssets>>,
-    mut step_query: Query<&PlayerAnimation>,
+/// Show the tool layer only while the current clip has one, e.g. the sword
+/// while attacking.
+fn update_tool_animation(
+    mut characters: Query<(&MovementController, &Children, &mut CharacterAnimation)>,
+    mut tool_sprites: Query<(&mut Sprite, &mut Visibility), With<CharacterTool>>,
 ) {
-    for animation in &mut step_query {
-        if animation.state == PlayerAnimationState::Walking
-            && animation.changed()
-            && (animation.frame == 2 || animation.frame == 5)
-        {
-            let rng = &mut rand::rng();
-            let random_step = player_assets.steps.choose(rng).unwrap().clone();
-            commands.spawn(sound_effect(random_step));
+    for (controller, children, animation) in &mut characters {
+        for &child in children {
+            if let Ok((mut sprite, mut visibility)) = tool_sprites.get_mut(child) {
+                let Some(tool_image) = &animation.clip.tool_image else {
+                    *visibility = Visibility::Hidden;
+                    continue;
+                };
+                *visibility = Visibility::Inherited;
+
+                let dx = controller.intent.x;
+                if dx != 0.0 {
+                    sprite.flip_x = dx < 0.0;
+                }
+
+                sprite.image = tool_image.clone();
+
+                let Some(atlas) = sprite.texture_atlas.as_mut() else {
+                    continue;
+                };
+                if animation.changed() {
+                    atlas.index = animation.frame;
+                }
+            }
         }
     }
 }
 
 #[derive(Clone, Reflect, Resource, Debug)]
 #[reflect(Resource)]
-pub struct PlayerAnimationClip {
+pub struct CharacterAnimationClip {
     pub base_image: Handle<Image>,
-    pub hair_image: Handle<Image>,
+    /// Drawn on top of the base image, for characters with a separate hair layer.
+    pub hair_image: Option<Handle<Image>>,
+    /// Drawn on top of everything else, for clips that show a tool or weapon.
+    pub tool_image: Option<Handle<Image>>,
     pub frames: usize,
     pub width: u32,
     pub height: u32,
     pub duration: Duration,
+    /// Whether the clip starts over after the last frame, or holds it.
+    pub looping: bool,
 }
 
-impl PlayerAnimationClip {
+impl CharacterAnimationClip {
     pub fn new(
         asset_server: &AssetServer,
         base_path: &'static str,
@@ -155,51 +200,114 @@ impl PlayerAnimationClip {
         height: u32,
     ) -> Self {
         Self {
-            base_image: asset_server.load_with_settings(
-                base_path,
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            hair_image: asset_server.load_with_settings(
-                hair_path,
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
+            hair_image: Some(load_pixel_art(asset_server, hair_path)),
+            ..Self::single(asset_server, base_path, frames, width, height)
+        }
+    }
+
+    /// A clip for characters that are drawn as a single layer, such as NPCs.
+    pub fn single(
+        asset_server: &AssetServer,
+        path: &'static str,
+        frames: usize,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            base_image: load_pixel_art(asset_server, path),
+            hair_image: None,
+            tool_image: None,
             frames,
             width,
             height,
             duration: Duration::from_millis(50), // 30 FPS?
+            looping: true,
         }
     }
+
+    /// Show a tool or weapon on top of the character while the clip plays.
+    pub fn with_tool(mut self, asset_server: &AssetServer, tool_path: &'static str) -> Self {
+        self.tool_image = Some(load_pixel_art(asset_server, tool_path));
+        self
+    }
+
+    /// Play the clip once and hold the last frame instead of looping.
+    pub fn once(mut self) -> Self {
+        self.looping = false;
+        self
+    }
+}
+
+/// The clips a character can play, by animation state. States without a clip
+/// keep playing whatever was playing before.
+#[derive(Component, Clone, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct CharacterClips(pub HashMap<CharacterAnimationState, CharacterAnimationClip>);
+
+impl CharacterClips {
+    /// A texture atlas layout that fits every clip. All clips of a character
+    /// share one layout, so it needs to be as wide as the longest strip.
+    pub fn atlas_layout(&self) -> TextureAtlasLayout {
+        let columns = self.0.values().map(|clip| clip.frames).max().unwrap_or(1);
+        let size = self
+            .0
+            .values()
+            .next()
+            .map_or(UVec2::ONE, |clip| UVec2::new(clip.width, clip.height));
+        TextureAtlasLayout::from_grid(size, columns as u32, 1, None, None)
+    }
 }
 
-/// Component that tracks player's animation state.
+/// The sprite child of a character that shows its body.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct CharacterBody;
+
+/// The sprite child of a character that shows its hair.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct CharacterHair;
+
+/// The sprite child of a character that shows the tool it's using, if any.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct CharacterTool;
+
+/// Component that tracks a character's animation state.
 /// It is tightly bound to the texture atlas we use.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-pub struct PlayerAnimation {
+pub struct CharacterAnimation {
     timer: Timer,
     pub frame: usize,
-    state: PlayerAnimationState,
-    clip: PlayerAnimationClip,
+    state: CharacterAnimationState,
+    clip: CharacterAnimationClip,
 }
 
 #[derive(Reflect, PartialEq, Eq, Hash, Clone, Copy, Debug)]
-pub enum PlayerAnimationState {
+pub enum CharacterAnimationState {
     Idling,
     Walking,
+    CarryIdling,
+    CarryWalking,
+    Casting,
+    Waiting,
+    Reeling,
+    Caught,
+    Attacking,
+    Digging,
+    Watering,
+    Doing,
+    Hurt,
+    Dying,
 }
 
-impl PlayerAnimation {
-    pub fn new(start_clip: &PlayerAnimationClip) -> Self {
+impl CharacterAnimation {
+    pub fn new(start_clip: &CharacterAnimationClip) -> Self {
         Self {
             timer: Timer::new(start_clip.duration, TimerMode::Repeating),
             frame: 0,
-            state: PlayerAnimationState::Idling,
+            state: CharacterAnimationState::Idling,
             clip: start_clip.clone(),
         }
     }
@@ -210,14 +318,18 @@ impl PlayerAnimation {
         if !self.timer.is_finished() {
             return;
         }
-        self.frame = (self.frame + 1) % self.clip.frames;
+        self.frame = if self.clip.looping {
+            (self.frame + 1) % self.clip.frames
+        } else {
+            (self.frame + 1).min(self.clip.frames - 1)
+        };
     }
 
     /// Update animation state if it changes.
     pub fn update_state(
         &mut self,
-        state: PlayerAnimationState,
-        clip: Option<&PlayerAnimationClip>,
+        state: CharacterAnimationState,
+        clip: Option<&CharacterAnimationClip>,
     ) {
         if self.state != state
             && let Some(clip) = clip
@@ -235,4 +347,14 @@ impl PlayerAnimation {
     pub fn changed(&self) -> bool {
         self.timer.is_finished()
     }
+
+    /// The current animation state.
+    pub fn state(&self) -> CharacterAnimationState {
+        self.state
+    }
+
+    /// Whether a non-looping clip has reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        !self.clip.looping && self.frame + 1 >= self.clip.frames
+    }
 }
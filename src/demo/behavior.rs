@@ -0,0 +1,437 @@
+//! NPC behavior: a small state machine per NPC that decides where it wants to
+//! go and steers its [`MovementController`] there.
+//!
+//! NPCs wander around their home, or walk a patrol route authored in Tiled as
+//! a polyline with a [`PatrolRoute`] property. When the [`Player`] comes into
+//! sight, hostile NPCs chase them and timid ones flee. A chasing NPC gives up
+//! and walks back home once it strays beyond its leash. NPCs show an [`Emote`]
+//! when they spot the player and when they give up on them.
+//! NPCs find their way around obstacles with [`NavGrids`].
+
+use std::{collections::HashMap, f32::consts::TAU, time::Duration};
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        combat::Attacking,
+        emote::{Emote, EmoteKind},
+        health::{Dead, Health},
+        movement::MovementController,
+        navigation::NavGrids,
+        player::Player,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Behavior>()
+        .register_type::<Patrol>()
+        .register_type::<PatrolRoute>()
+        .init_resource::<PatrolRoutes>();
+
+    app.add_systems(
+        Update,
+        (
+            register_patrol_routes,
+            update_behavior
+                .in_set(AppSystems::RecordInput)
+                .in_set(PausableSystems),
+        ),
+    );
+}
+
+/// How close an NPC needs to get to a point to count as having reached it.
+const ARRIVE_DISTANCE: f32 = 4.0;
+
+/// How fast NPCs move when they're not in a hurry, relative to their maximum
+/// speed.
+const STROLL_SPEED: f32 = 0.5;
+
+/// How long an NPC keeps trying to reach a point without getting any closer,
+/// e.g. because something is in the way, in seconds.
+const GIVE_UP_SECS: f32 = 2.0;
+
+/// Below this fraction of their health, hostile NPCs flee instead of chasing.
+const FLEE_HEALTH_FRACTION: f32 = 0.25;
+
+/// How an NPC reacts to seeing the player.
+#[derive(Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Temperament {
+    /// Ignores the player.
+    #[default]
+    Passive,
+    /// Chases the player.
+    Hostile,
+    /// Runs away from the player.
+    Timid,
+}
+
+/// Drives an NPC's [`MovementController`] intent.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Behavior {
+    pub temperament: Temperament,
+    /// How close the player needs to be for the NPC to notice them.
+    pub sight_radius: f32,
+    /// How far from home the NPC chases the player before giving up.
+    pub leash_radius: f32,
+    /// How far from home the NPC strays while wandering.
+    pub wander_radius: f32,
+    /// Confines wandering to an area, e.g. an animal pen, instead of a circle
+    /// around home.
+    pub wander_area: Option<Rect>,
+    /// Where the NPC started out. Set the first time it thinks.
+    pub home: Option<Vec2>,
+    pub state: BehaviorState,
+}
+
+impl Default for Behavior {
+    fn default() -> Self {
+        Self {
+            temperament: Temperament::default(),
+            sight_radius: 64.0,
+            leash_radius: 160.0,
+            wander_radius: 48.0,
+            wander_area: None,
+            home: None,
+            state: BehaviorState::idle(),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub enum BehaviorState {
+    /// Standing around until the timer runs out.
+    Idle(Timer),
+    /// Strolling to a random point near home, or inside the wander area.
+    Wander(Trip),
+    /// Walking back and forth along the NPC's patrol route. There is no trip
+    /// while the next waypoint can't be reached.
+    Patrol {
+        next: usize,
+        reverse: bool,
+        trip: Option<Trip>,
+    },
+    Chase,
+    Flee,
+    /// Walking back home after a chase. The player is ignored until then.
+    ReturnHome(Trip),
+}
+
+impl BehaviorState {
+    /// Idle for a random short while.
+    fn idle() -> Self {
+        let secs = rand::rng().random_range(1.0..3.0);
+        Self::Idle(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+/// The way to a point, found once with [`NavGrids`] and then followed waypoint
+/// by waypoint.
+#[derive(Reflect, Debug, Clone)]
+pub struct Trip {
+    target: Vec2,
+    /// The waypoints that are still ahead, ending at the target.
+    path: Vec<Vec2>,
+    progress: Progress,
+}
+
+impl Trip {
+    /// Plans a trip from `pos` to `target`, if there is a way there.
+    fn plan(nav_grids: &NavGrids, pos: Vec2, target: Vec2) -> Option<Self> {
+        Some(Self {
+            target,
+            path: nav_grids.find_path(pos, target)?,
+            progress: Progress::default(),
+        })
+    }
+
+    fn has_arrived(&self, pos: Vec2) -> bool {
+        pos.distance(self.target) <= ARRIVE_DISTANCE
+    }
+
+    /// Whether the NPC, now at `pos`, hasn't come any closer to the end of the
+    /// trip for [`GIVE_UP_SECS`], e.g. because something is in the way.
+    fn is_stuck(&mut self, pos: Vec2, delta: Duration) -> bool {
+        let (remaining, _) = self
+            .path
+            .iter()
+            .fold((0.0, pos), |(distance, from), &waypoint| {
+                (distance + from.distance(waypoint), waypoint)
+            });
+        self.progress.is_stuck(remaining, delta)
+    }
+
+    /// The direction to walk in from `pos`, or zero once the NPC has arrived.
+    fn steer(&mut self, pos: Vec2) -> Vec2 {
+        if self.has_arrived(pos) {
+            return Vec2::ZERO;
+        }
+        while self.path.len() > 1 && pos.distance(self.path[0]) <= ARRIVE_DISTANCE {
+            self.path.remove(0);
+        }
+        let waypoint = self.path.first().copied().unwrap_or(self.target);
+        (waypoint - pos).normalize_or_zero()
+    }
+}
+
+/// Notices when an NPC stops getting closer to where it's going.
+#[derive(Reflect, Debug, Clone)]
+pub struct Progress {
+    /// The closest the NPC has come to its target so far.
+    closest: f32,
+    timer: Timer,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self {
+            closest: f32::INFINITY,
+            timer: Timer::from_seconds(GIVE_UP_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Progress {
+    /// Whether the NPC, now `distance` away from where it's going, hasn't come
+    /// any closer for [`GIVE_UP_SECS`].
+    fn is_stuck(&mut self, distance: f32, delta: Duration) -> bool {
+        if distance < self.closest - 1.0 {
+            self.closest = distance;
+            self.timer.reset();
+        }
+        self.timer.tick(delta).is_finished()
+    }
+}
+
+/// Makes an NPC walk the [`PatrolRoute`] with the given id instead of
+/// wandering around.
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct Patrol {
+    route: String,
+}
+
+/// Marks a Tiled polyline as a patrol route for NPCs with a matching
+/// [`Patrol`].
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct PatrolRoute {
+    id: String,
+}
+
+#[derive(Resource, Default, Debug, Reflect)]
+#[reflect(Resource, Default)]
+struct PatrolRoutes {
+    map: HashMap<String, Entity>,
+}
+
+impl PatrolRoutes {
+    /// The route's points in world space, if it exists and is a polyline.
+    fn points(
+        &self,
+        id: &str,
+        routes: &Query<(&TiledObject, &GlobalTransform), With<PatrolRoute>>,
+    ) -> Option<Vec<Vec2>> {
+        let (object, transform) = routes.get(*self.map.get(id)?).ok()?;
+        let TiledObject::Polyline { vertices } = object else {
+            return None;
+        };
+        Some(
+            vertices
+                .iter()
+                .map(|vertex| transform.transform_point(vertex.extend(0.0)).truncate())
+                .collect(),
+        )
+    }
+}
+
+fn register_patrol_routes(
+    routes: Query<(Entity, &PatrolRoute), Added<PatrolRoute>>,
+    mut lookup: ResMut<PatrolRoutes>,
+) {
+    for (entity, route) in &routes {
+        lookup.map.insert(route.id.clone(), entity);
+    }
+}
+
+fn update_behavior(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Query<&GlobalTransform, (With<Player>, Without<Dead>)>,
+    mut npcs: Query<
+        (
+            Entity,
+            &mut Behavior,
+            &mut MovementController,
+            &GlobalTransform,
+            Option<&Patrol>,
+            Option<&Health>,
+        ),
+        (Without<Dead>, Without<Attacking>),
+    >,
+    patrol_routes: Res<PatrolRoutes>,
+    routes: Query<(&TiledObject, &GlobalTransform), With<PatrolRoute>>,
+    nav_grids: Res<NavGrids>,
+) {
+    let player_pos = player
+        .single()
+        .ok()
+        .map(|transform| transform.translation().truncate());
+
+    for (entity, mut behavior, mut controller, transform, patrol, health) in &mut npcs {
+        let behavior = &mut *behavior;
+        let pos = transform.translation().truncate();
+        let home = *behavior.home.get_or_insert(pos);
+        let route = patrol
+            .and_then(|patrol| patrol_routes.points(&patrol.route, &routes))
+            .filter(|route| !route.is_empty());
+        let seen_player =
+            player_pos.filter(|player_pos| player_pos.distance(pos) <= behavior.sight_radius);
+        let wants_to_flee = match behavior.temperament {
+            Temperament::Passive => false,
+            Temperament::Timid => true,
+            Temperament::Hostile => {
+                health.is_some_and(|health| health.current <= health.max * FLEE_HEALTH_FRACTION)
+            }
+        };
+
+        if let BehaviorState::Idle(timer) = &mut behavior.state {
+            timer.tick(time.delta());
+        }
+
+        // Decide what to do.
+        let next_state = match &mut behavior.state {
+            // Give up on getting home if the way is blocked, e.g. by a crate.
+            BehaviorState::ReturnHome(trip) => (trip.has_arrived(pos)
+                || trip.is_stuck(pos, time.delta()))
+            .then(BehaviorState::idle),
+            BehaviorState::Flee if seen_player.is_none() => Some(BehaviorState::idle()),
+            BehaviorState::Chase
+                if seen_player.is_none() || pos.distance(home) > behavior.leash_radius =>
+            {
+                Some(
+                    Trip::plan(&nav_grids, pos, home)
+                        .map_or_else(BehaviorState::idle, BehaviorState::ReturnHome),
+                )
+            }
+            _ if seen_player.is_some() && wants_to_flee => Some(BehaviorState::Flee),
+            _ if seen_player.is_some() && behavior.temperament == Temperament::Hostile => {
+                Some(BehaviorState::Chase)
+            }
+            BehaviorState::Idle(timer) if timer.is_finished() => Some(match &route {
+                Some(route) => {
+                    let next = nearest_point(route, pos);
+                    BehaviorState::Patrol {
+                        next,
+                        reverse: false,
+                        trip: Trip::plan(&nav_grids, pos, route[next]),
+                    }
+                }
+                None => {
+                    let rng = &mut rand::rng();
+                    let target = match behavior.wander_area {
+                        Some(area) => Vec2::new(
+                            rng.random_range(area.min.x..=area.max.x),
+                            rng.random_range(area.min.y..=area.max.y),
+                        ),
+                        None => {
+                            home + Vec2::from_angle(rng.random_range(0.0..TAU))
+                                * rng.random_range(0.0..behavior.wander_radius)
+                        }
+                    };
+                    Trip::plan(&nav_grids, pos, target)
+                        .map_or_else(BehaviorState::idle, BehaviorState::Wander)
+                }
+            }),
+            // Give up on targets that can't be reached anymore, e.g. because
+            // something is in the way.
+            BehaviorState::Wander(trip) => (trip.has_arrived(pos)
+                || trip.is_stuck(pos, time.delta()))
+            .then(BehaviorState::idle),
+            BehaviorState::Patrol {
+                next,
+                reverse,
+                trip,
+            } => {
+                // Skip waypoints that can't be reached, like wander targets.
+                if let Some(route) = &route
+                    && trip.as_mut().is_none_or(|trip| {
+                        trip.has_arrived(pos) || trip.is_stuck(pos, time.delta())
+                    })
+                {
+                    // Turn around at either end of the route.
+                    if (*reverse && *next == 0) || (!*reverse && *next + 1 == route.len()) {
+                        *reverse = !*reverse;
+                    }
+                    if *reverse {
+                        *next = next.saturating_sub(1);
+                    } else {
+                        *next = (*next + 1).min(route.len() - 1);
+                    }
+                    *trip = Trip::plan(&nav_grids, pos, route[*next]);
+                }
+                None
+            }
+            _ => None,
+        };
+        if let Some(next_state) = next_state {
+            let emote = match (&behavior.state, &next_state) {
+                (BehaviorState::Chase, BehaviorState::ReturnHome(_) | BehaviorState::Idle(_)) => {
+                    Some(EmoteKind::Confused)
+                }
+                // Chasing and fleeing are re-decided every frame.
+                (BehaviorState::Chase, BehaviorState::Chase)
+                | (BehaviorState::Flee, BehaviorState::Flee) => None,
+                (_, BehaviorState::Chase) => Some(EmoteKind::Alerted),
+                (_, BehaviorState::Flee) => Some(EmoteKind::Stress),
+                _ => None,
+            };
+            if let Some(emote) = emote {
+                commands.trigger(Emote::new(entity, emote));
+            }
+            behavior.state = next_state;
+        }
+
+        // Steer towards wherever the current state wants to go.
+        controller.intent = match &mut behavior.state {
+            BehaviorState::Idle(_) => Vec2::ZERO,
+            BehaviorState::Wander(trip) => trip.steer(pos) * STROLL_SPEED,
+            BehaviorState::Patrol { trip, .. } => trip
+                .as_mut()
+                .map_or(Vec2::ZERO, |trip| trip.steer(pos) * STROLL_SPEED),
+            BehaviorState::Chase => {
+                player_pos.map_or(Vec2::ZERO, |target| steer(&nav_grids, pos, target))
+            }
+            BehaviorState::Flee => {
+                player_pos.map_or(Vec2::ZERO, |threat| (pos - threat).normalize_or_zero())
+            }
+            BehaviorState::ReturnHome(trip) => trip.steer(pos),
+        };
+    }
+}
+
+/// The direction to walk in to get from `pos` to a moving `target`, or zero
+/// once it's been reached. Follows a path around obstacles if there is one,
+/// and heads straight for the target otherwise.
+fn steer(nav_grids: &NavGrids, pos: Vec2, target: Vec2) -> Vec2 {
+    if pos.distance(target) <= ARRIVE_DISTANCE {
+        return Vec2::ZERO;
+    }
+    let waypoint = nav_grids
+        .find_path(pos, target)
+        .and_then(|path| path.first().copied())
+        .unwrap_or(target);
+    (waypoint - pos).normalize_or_zero()
+}
+
+fn nearest_point(route: &[Vec2], pos: Vec2) -> usize {
+    route
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.distance(pos).total_cmp(&b.distance(pos)))
+        .map_or(0, |(index, _)| index)
+}
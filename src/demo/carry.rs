@@ -0,0 +1,143 @@
+//! Picking up objects and carrying them above the player's head.
+//!
+//! Objects are marked as [`Carryable`] in Tiled. Interacting with one lifts it
+//! above the player's head, and interacting again puts it down on the free
+//! tile in front of the player.
+
+use avian2d::prelude::{ColliderDisabled, SpatialQuery, SpatialQueryFilter};
+use bevy::prelude::*;
+
+use crate::{
+    demo::{
+        interaction::{Interactable, Interacted},
+        level::TILE_SIZE,
+        movement::MovementController,
+        navigation::NavObstacle,
+        tiles::MapTiles,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Carryable>()
+        .register_type::<CarriedBy>()
+        .register_type::<Carrying>();
+
+    app.add_observer(pick_up_or_put_down);
+}
+
+/// How close the player needs to be to an object to pick it up.
+const PICK_UP_RANGE: f32 = 20.0;
+
+const PICK_UP_LABEL: &str = "Pick up";
+const PUT_DOWN_LABEL: &str = "Put down";
+
+/// Where a carried object sits relative to its carrier.
+const CARRY_OFFSET: Vec3 = Vec3::new(0.0, 14.0, 1.0);
+
+/// An object that can be picked up and carried, such as a crate or a crop.
+/// NPCs walk around it while it's on the ground.
+#[derive(Component, Default, Debug, Reflect, PartialEq, Eq, Clone, Copy)]
+#[reflect(Component, Default)]
+#[require(NavObstacle, Interactable = Interactable::new(PICK_UP_LABEL, PICK_UP_RANGE))]
+pub struct Carryable;
+
+/// The entity that is currently carrying this object.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+#[relationship(relationship_target = Carrying)]
+pub struct CarriedBy(pub Entity);
+
+/// The object this entity is currently carrying.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+#[relationship_target(relationship = CarriedBy)]
+pub struct Carrying(Entity);
+
+impl Carrying {
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+fn pick_up_or_put_down(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    carriers: Query<(&GlobalTransform, &MovementController, Has<Carrying>)>,
+    mut objects: Query<(Option<&CarriedBy>, &mut Interactable), With<Carryable>>,
+    carryables: Query<(Entity, &GlobalTransform), (With<Carryable>, Without<CarriedBy>)>,
+    map_tiles: MapTiles,
+    spatial_query: SpatialQuery,
+) {
+    let object = interacted.entity;
+    let carrier = interacted.interactor;
+    let Ok((carried_by, mut interactable)) = objects.get_mut(object) else {
+        return;
+    };
+    let Ok((carrier_transform, controller, carrying)) = carriers.get(carrier) else {
+        return;
+    };
+    let carrier_pos = carrier_transform.translation();
+
+    if carried_by.is_some_and(|carried_by| carried_by.0 == carrier) {
+        let Some(tile) = free_tile_in_front(
+            carrier_pos.truncate(),
+            controller.facing,
+            &[carrier, object],
+            &carryables,
+            &map_tiles,
+            &spatial_query,
+        ) else {
+            return;
+        };
+
+        commands
+            .entity(object)
+            .remove::<(CarriedBy, ChildOf, ColliderDisabled)>()
+            .insert((
+                Transform::from_translation(tile.extend(carrier_pos.z)),
+                DespawnOnExit(Screen::Gameplay),
+            ));
+        interactable.label = PICK_UP_LABEL.into();
+        return;
+    }
+
+    if carried_by.is_some() || carrying {
+        return;
+    }
+    commands.entity(object).insert((
+        CarriedBy(carrier),
+        ChildOf(carrier),
+        Transform::from_translation(CARRY_OFFSET),
+        // Don't let the carried object push its carrier around.
+        ColliderDisabled,
+    ));
+    interactable.label = PUT_DOWN_LABEL.into();
+}
+
+/// Finds the center of the tile in front of `origin`, if nothing occupies it.
+fn free_tile_in_front(
+    origin: Vec2,
+    facing: Vec2,
+    ignore: &[Entity],
+    carryables: &Query<(Entity, &GlobalTransform), (With<Carryable>, Without<CarriedBy>)>,
+    map_tiles: &MapTiles,
+    spatial_query: &SpatialQuery,
+) -> Option<Vec2> {
+    let tile = map_tiles.tile_center(origin + facing.normalize_or_zero() * TILE_SIZE)?;
+
+    let occupied_by_object = carryables.iter().any(|(_, transform)| {
+        let delta = (transform.translation().truncate() - tile).abs();
+        delta.x < TILE_SIZE * 0.5 && delta.y < TILE_SIZE * 0.5
+    });
+    if occupied_by_object {
+        return None;
+    }
+
+    let filter = SpatialQueryFilter::default().with_excluded_entities(ignore.iter().copied());
+    if !spatial_query.point_intersections(tile, &filter).is_empty() {
+        return None;
+    }
+
+    Some(tile)
+}
@@ -0,0 +1,297 @@
+//! The in-game clock, and the day/night cycle that follows it.
+//!
+//! [`GameClock`] advances while the game isn't paused. Observe [`HourChanged`]
+//! and [`DayStarted`] to do something at a certain time of day, or once per
+//! day.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::{LoadResource, load_pixel_art},
+    screens::Screen,
+    theme::palette::LABEL_TEXT,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GameClock>()
+        .register_type::<Season>()
+        .init_resource::<GameClock>();
+    app.load_resource::<ClockAssets>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_clock, spawn_daylight_tint, spawn_clock_hud),
+    );
+    app.add_systems(
+        Update,
+        (
+            advance_clock
+                .in_set(AppSystems::TickTimers)
+                .in_set(PausableSystems),
+            (update_daylight_tint, update_clock_hud).in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How long an in-game day lasts by default, in seconds.
+const DEFAULT_DAY_SECS: f32 = 300.0;
+
+/// How many days a season lasts by default.
+const DEFAULT_DAYS_PER_SEASON: u32 = 28;
+
+/// The hour a new game starts at.
+const START_HOUR: u32 = 6;
+
+const HOURS_PER_DAY: u32 = 24;
+const MINUTES_PER_HOUR: u32 = 60;
+
+/// How the scene is tinted over the course of a day, as `(hour, tint)` pairs.
+/// The tint is blended between neighboring hours.
+const DAYLIGHT: [(f32, Color); 7] = [
+    (0.0, Color::srgba(0.05, 0.05, 0.2, 0.55)),
+    (5.0, Color::srgba(0.05, 0.05, 0.2, 0.55)),
+    (7.0, Color::srgba(0.9, 0.5, 0.2, 0.15)),
+    (9.0, Color::srgba(1.0, 1.0, 1.0, 0.0)),
+    (17.0, Color::srgba(1.0, 1.0, 1.0, 0.0)),
+    (19.5, Color::srgba(0.9, 0.4, 0.2, 0.25)),
+    (22.0, Color::srgba(0.05, 0.05, 0.2, 0.55)),
+];
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    const ALL: [Self; 4] = [Self::Spring, Self::Summer, Self::Autumn, Self::Winter];
+}
+
+/// The in-game date and time.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct GameClock {
+    /// How long a day lasts, in real seconds.
+    pub day_secs: f32,
+    pub days_per_season: u32,
+    /// How many days have passed since the start of the game.
+    days: u32,
+    /// How far into the current day it is, from 0 (midnight) to 1.
+    time_of_day: f32,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self {
+            day_secs: DEFAULT_DAY_SECS,
+            days_per_season: DEFAULT_DAYS_PER_SEASON,
+            days: 0,
+            time_of_day: START_HOUR as f32 / HOURS_PER_DAY as f32,
+        }
+    }
+}
+
+impl GameClock {
+    /// The current day within the season, starting at 1.
+    pub fn day_of_season(&self) -> u32 {
+        self.days % self.days_per_season + 1
+    }
+
+    pub fn season(&self) -> Season {
+        let seasons = self.days / self.days_per_season;
+        Season::ALL[seasons as usize % Season::ALL.len()]
+    }
+
+    /// The current time in hours, e.g. 13.5 at half past one.
+    pub fn hours(&self) -> f32 {
+        self.time_of_day * HOURS_PER_DAY as f32
+    }
+
+    pub fn hour(&self) -> u32 {
+        self.hours() as u32
+    }
+
+    pub fn minute(&self) -> u32 {
+        (self.hours().fract() * MINUTES_PER_HOUR as f32) as u32
+    }
+
+    /// How light it is, from 0 at night to 1 during the day. Follows the
+    /// daylight tint, so dawn and dusk are in between.
+    pub fn daylight(&self) -> f32 {
+        1.0 - daylight_tint(self.hours()).alpha() / DAYLIGHT[0].1.alpha()
+    }
+
+    /// How many hours have passed since the start of the game.
+    fn total_hours(&self) -> u32 {
+        self.days * HOURS_PER_DAY + self.hour()
+    }
+}
+
+/// Triggered whenever a new hour starts. The [`GameClock`] has the new time.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HourChanged;
+
+/// Triggered at midnight, when a new day starts. The [`GameClock`] has the new
+/// date.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DayStarted;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ClockAssets {
+    #[dependency]
+    stopwatch: Handle<Image>,
+    #[dependency]
+    sandtimer: Handle<Image>,
+}
+
+impl FromWorld for ClockAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        let image = |path| -> Handle<Image> { load_pixel_art(assets, path) };
+        Self {
+            stopwatch: image("UI/stopwatch.png"),
+            sandtimer: image("UI/sandtimer.png"),
+        }
+    }
+}
+
+fn reset_clock(mut clock: ResMut<GameClock>) {
+    *clock = GameClock::default();
+}
+
+fn advance_clock(mut commands: Commands, time: Res<Time>, mut clock: ResMut<GameClock>) {
+    let hours_before = clock.total_hours();
+    clock.time_of_day += time.delta_secs() / clock.day_secs;
+    while clock.time_of_day >= 1.0 {
+        clock.time_of_day -= 1.0;
+        clock.days += 1;
+    }
+
+    for hours in hours_before + 1..=clock.total_hours() {
+        commands.trigger(HourChanged);
+        if hours % HOURS_PER_DAY == 0 {
+            commands.trigger(DayStarted);
+        }
+    }
+}
+
+/// Tints everything in the world, but not the HUD.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DaylightTint;
+
+fn spawn_daylight_tint(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Daylight Tint"),
+        DaylightTint,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        // Stay below the rest of the UI.
+        GlobalZIndex(-1),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn update_daylight_tint(
+    clock: Res<GameClock>,
+    mut tint: Single<&mut BackgroundColor, With<DaylightTint>>,
+) {
+    tint.0 = daylight_tint(clock.hours());
+}
+
+fn daylight_tint(hours: f32) -> Color {
+    DAYLIGHT.windows(2).find(|pair| hours < pair[1].0).map_or(
+        DAYLIGHT[DAYLIGHT.len() - 1].1,
+        |pair| {
+            let (from_hour, from) = pair[0];
+            let (to_hour, to) = pair[1];
+            from.mix(&to, (hours - from_hour) / (to_hour - from_hour))
+        },
+    )
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ClockTime;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ClockDate;
+
+fn spawn_clock_hud(mut commands: Commands, clock_assets: Res<ClockAssets>) {
+    let row = |icon: &Handle<Image>| {
+        (
+            Node {
+                align_items: AlignItems::Center,
+                column_gap: px(8),
+                ..default()
+            },
+            children![(
+                Node {
+                    width: px(32),
+                    height: px(32),
+                    ..default()
+                },
+                ImageNode::new(icon.clone()),
+            )],
+        )
+    };
+    let text = || {
+        (
+            Text::default(),
+            TextFont::from_font_size(20.0),
+            TextColor(LABEL_TEXT),
+            TextShadow::default(),
+        )
+    };
+
+    commands
+        .spawn((
+            Name::new("Clock"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(8),
+                right: px(8),
+                flex_direction: FlexDirection::Column,
+                row_gap: px(4),
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((Name::new("Clock Time"), row(&clock_assets.stopwatch)))
+                .with_child((ClockTime, text()));
+            parent
+                .spawn((Name::new("Clock Date"), row(&clock_assets.sandtimer)))
+                .with_child((ClockDate, text()));
+        });
+}
+
+fn update_clock_hud(
+    clock: Res<GameClock>,
+    mut time_text: Single<&mut Text, (With<ClockTime>, Without<ClockDate>)>,
+    mut date_text: Single<&mut Text, (With<ClockDate>, Without<ClockTime>)>,
+) {
+    let time = format!("{:02}:{:02}", clock.hour(), clock.minute());
+    if time_text.0 != time {
+        time_text.0 = time;
+    }
+    let date = format!("{:?} {}", clock.season(), clock.day_of_season());
+    if date_text.0 != date {
+        date_text.0 = date;
+    }
+}
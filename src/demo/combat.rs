@@ -0,0 +1,284 @@
+//! Melee combat.
+//!
+//! Characters with a [`MeleeAttack`] can swing at their enemies. The player
+//! attacks with the attack key, and hostile NPCs attack when the player is in
+//! reach. On the impact frame of the attack animation a short-lived [`Hitbox`]
+//! sensor appears in front of the attacker, dealing [`Damage`] to everything
+//! with [`Health`] on another [`Team`] that it touches. Each swing hits any
+//! given target at most once.
+
+use avian2d::prelude::{Collider, CollidingEntities, Sensor};
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        animation::{CharacterAnimation, CharacterAnimationState},
+        behavior::{Behavior, BehaviorState},
+        carry::Carrying,
+        farming::Working,
+        fishing::Fishing,
+        health::{Damage, Dead, Health, Hit},
+        movement::MovementController,
+        player::Player,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Team>()
+        .register_type::<MeleeAttack>()
+        .register_type::<Attacking>()
+        .register_type::<AttackCooldown>()
+        .register_type::<Hitbox>();
+
+    app.add_systems(
+        Update,
+        (
+            (tick_attack_cooldowns, tick_hitboxes).in_set(AppSystems::TickTimers),
+            (
+                player_attack.run_if(input_just_pressed(ATTACK_KEY)),
+                npc_attack,
+            )
+                .in_set(AppSystems::RecordInput),
+            (advance_attacks, apply_hitbox_hits)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(interrupt_attack_on_hit);
+}
+
+const ATTACK_KEY: KeyCode = KeyCode::Space;
+
+/// How long a hitbox stays around after the impact frame, in seconds.
+const HITBOX_SECS: f32 = 0.1;
+
+/// The radius of a hitbox.
+const HITBOX_RADIUS: f32 = 8.0;
+
+/// Characters only damage characters on other teams.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum Team {
+    Player,
+    Monsters,
+}
+
+/// Lets a character swing at its enemies.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct MeleeAttack {
+    pub damage: f32,
+    /// The impulse to knock targets back with.
+    pub knockback: f32,
+    /// How far in front of the attacker the hitbox appears.
+    pub reach: f32,
+    /// The frame of the attack animation on which the hit lands.
+    pub impact_frame: usize,
+    /// How long to wait after a swing before attacking again, in seconds.
+    pub cooldown_secs: f32,
+}
+
+/// Present on a character while they're swinging.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Attacking {
+    /// Whether the hitbox has already been spawned for this swing.
+    struck: bool,
+}
+
+/// The character can't attack again until the timer runs out.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct AttackCooldown(Timer);
+
+/// A sensor that damages the enemies it touches.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Hitbox {
+    attacker: Entity,
+    team: Team,
+    damage: f32,
+    knockback: f32,
+    /// Everyone this swing has already hit.
+    hit: Vec<Entity>,
+    lifetime: Timer,
+}
+
+fn tick_attack_cooldowns(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut AttackCooldown)>,
+) {
+    for (entity, mut cooldown) in &mut query {
+        if cooldown.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<AttackCooldown>();
+        }
+    }
+}
+
+fn tick_hitboxes(mut commands: Commands, time: Res<Time>, mut query: Query<(Entity, &mut Hitbox)>) {
+    for (entity, mut hitbox) in &mut query {
+        if hitbox.lifetime.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn player_attack(
+    mut commands: Commands,
+    player: Single<
+        Entity,
+        (
+            With<Player>,
+            With<MeleeAttack>,
+            Without<Attacking>,
+            Without<AttackCooldown>,
+            Without<Dead>,
+            Without<Fishing>,
+            Without<Carrying>,
+            Without<Working>,
+        ),
+    >,
+) {
+    commands.entity(*player).insert(Attacking::default());
+}
+
+/// Hostile NPCs swing at the player once they've caught up with them.
+fn npc_attack(
+    mut commands: Commands,
+    player: Query<&GlobalTransform, (With<Player>, Without<Dead>)>,
+    mut npcs: Query<
+        (
+            Entity,
+            &Behavior,
+            &MeleeAttack,
+            &GlobalTransform,
+            &mut MovementController,
+        ),
+        (Without<Attacking>, Without<AttackCooldown>, Without<Dead>),
+    >,
+) {
+    let Ok(player_transform) = player.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation().truncate();
+
+    for (entity, behavior, attack, transform, mut controller) in &mut npcs {
+        let pos = transform.translation().truncate();
+        if matches!(behavior.state, BehaviorState::Chase)
+            && pos.distance(player_pos) <= attack.reach + HITBOX_RADIUS
+        {
+            // Face the player before swinging.
+            controller.facing = (player_pos - pos).normalize_or(controller.facing);
+            commands.entity(entity).insert(Attacking::default());
+        }
+    }
+}
+
+/// Holds the attacker in place, spawns the hitbox on the impact frame and ends
+/// the swing once the animation is done.
+fn advance_attacks(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut Attacking,
+        &MeleeAttack,
+        &Team,
+        &mut MovementController,
+        &CharacterAnimation,
+    )>,
+) {
+    for (entity, mut attacking, attack, &team, mut controller, animation) in &mut query {
+        controller.intent = Vec2::ZERO;
+        if animation.state() != CharacterAnimationState::Attacking {
+            continue;
+        }
+
+        if !attacking.struck && animation.frame >= attack.impact_frame {
+            attacking.struck = true;
+            let offset = controller.facing.normalize_or_zero() * attack.reach;
+            commands.spawn((
+                Name::new("Hitbox"),
+                Hitbox {
+                    attacker: entity,
+                    team,
+                    damage: attack.damage,
+                    knockback: attack.knockback,
+                    hit: Vec::new(),
+                    lifetime: Timer::from_seconds(HITBOX_SECS, TimerMode::Once),
+                },
+                Sensor,
+                Collider::circle(HITBOX_RADIUS),
+                CollidingEntities::default(),
+                Transform::from_translation(offset.extend(0.0)),
+                ChildOf(entity),
+            ));
+        }
+
+        if animation.is_finished() {
+            commands
+                .entity(entity)
+                .remove::<Attacking>()
+                .insert(AttackCooldown(Timer::from_seconds(
+                    attack.cooldown_secs,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+fn apply_hitbox_hits(
+    mut commands: Commands,
+    mut hitboxes: Query<(&mut Hitbox, &CollidingEntities)>,
+    targets: Query<(&Team, &GlobalTransform), With<Health>>,
+    attackers: Query<&GlobalTransform>,
+) {
+    for (mut hitbox, colliding) in &mut hitboxes {
+        let Ok(attacker_transform) = attackers.get(hitbox.attacker) else {
+            continue;
+        };
+        let attacker_pos = attacker_transform.translation().truncate();
+
+        for &target in colliding.iter() {
+            if target == hitbox.attacker || hitbox.hit.contains(&target) {
+                continue;
+            }
+            let Ok((&team, target_transform)) = targets.get(target) else {
+                continue;
+            };
+            if team == hitbox.team {
+                continue;
+            }
+
+            hitbox.hit.push(target);
+            let direction =
+                (target_transform.translation().truncate() - attacker_pos).normalize_or_zero();
+            commands.trigger(Damage {
+                entity: target,
+                amount: hitbox.damage,
+                knockback: direction * hitbox.knockback,
+            });
+        }
+    }
+}
+
+/// Getting hit cuts a swing short. The attacker still has to wait for the
+/// cooldown before swinging again.
+fn interrupt_attack_on_hit(
+    hit: On<Hit>,
+    mut commands: Commands,
+    attackers: Query<&MeleeAttack, With<Attacking>>,
+) {
+    if let Ok(attack) = attackers.get(hit.entity) {
+        commands
+            .entity(hit.entity)
+            .remove::<Attacking>()
+            .insert(AttackCooldown(Timer::from_seconds(
+                attack.cooldown_secs,
+                TimerMode::Once,
+            )));
+    }
+}
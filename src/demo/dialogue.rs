@@ -0,0 +1,540 @@
+//! Branching conversations with NPCs, signs and anything else with [`Talk`].
+//!
+//! Dialogue trees are authored as RON files in `assets/data/dialogue`. Each
+//! node is a line of text with an optional speaker, and either a list of
+//! choices or where to go next. Nodes and choices can set [`DialogueFlags`],
+//! and choices and jumps can be made conditional on them. Nodes can also pop
+//! up an [`Emote`] above whoever the player is talking to.
+//!
+//! The world is paused while a conversation is running.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Pause,
+    asset_tracking::{LoadResource, load_pixel_art},
+    data::LoadRonAsset,
+    demo::{
+        emote::{Emote, EmoteKind},
+        interaction::{Interactable, Interacted},
+    },
+    menus::Menu,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Talk>()
+        .register_type::<DialogueFlags>()
+        .init_resource::<DialogueFlags>();
+    app.init_ron_asset::<DialogueTree>("dialogue.ron");
+    app.load_resource::<DialogueAssets>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_flags);
+    app.add_systems(Update, load_talk_dialogue);
+    app.add_systems(
+        Update,
+        (tick_typewriter, advance_dialogue, update_dialogue_ui)
+            .chain()
+            .run_if(resource_exists::<ActiveDialogue>.and(in_state(Menu::None))),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), end_dialogue);
+
+    app.add_observer(start_dialogue);
+}
+
+/// How close the player needs to be to talk to someone.
+const TALK_RANGE: f32 = 24.0;
+
+/// How many characters the typewriter reveals per second.
+const TYPEWRITER_CHARS_PER_SEC: f32 = 40.0;
+
+const ADVANCE_KEYS: [KeyCode; 3] = [KeyCode::KeyE, KeyCode::Space, KeyCode::Enter];
+const PREVIOUS_CHOICE_KEYS: [KeyCode; 2] = [KeyCode::KeyW, KeyCode::ArrowUp];
+const NEXT_CHOICE_KEYS: [KeyCode; 2] = [KeyCode::KeyS, KeyCode::ArrowDown];
+
+/// How much the 9-slice box pieces are scaled up.
+const BOX_SCALE: f32 = 4.0;
+
+/// The size of a single 9-slice box piece, in pixels.
+const BOX_PIECE_SIZE: f32 = 3.0;
+
+const DIALOGUE_TEXT: Color = Color::srgb(0.157, 0.157, 0.157);
+const SPEAKER_TEXT: Color = Color::srgb(0.275, 0.400, 0.750);
+const SELECTED_CHOICE_TEXT: Color = Color::srgb(0.239, 0.286, 0.600);
+const CHOICE_TEXT: Color = Color::srgb(0.400, 0.400, 0.400);
+
+/// Something the player can talk to. Set `dialogue` to the path of a dialogue
+/// tree, e.g. `data/dialogue/goblin.dialogue.ron`.
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[require(Interactable = Interactable::new("Talk", TALK_RANGE))]
+pub struct Talk {
+    dialogue: String,
+}
+
+/// The loaded dialogue tree of a [`Talk`].
+#[derive(Component, Debug)]
+struct TalkDialogue(Handle<DialogueTree>);
+
+/// Flags set by conversations, e.g. whether the player has met someone.
+#[derive(Resource, Serialize, Deserialize, Default, Debug, Clone, Reflect)]
+#[reflect(Resource, Default)]
+pub struct DialogueFlags(pub HashSet<String>);
+
+impl DialogueFlags {
+    fn holds(&self, condition: Option<&Condition>) -> bool {
+        match condition {
+            None => true,
+            Some(Condition::Flag(flag)) => self.0.contains(flag),
+            Some(Condition::NotFlag(flag)) => !self.0.contains(flag),
+        }
+    }
+
+    fn set_all(&mut self, flags: &[String]) {
+        self.0.extend(flags.iter().cloned());
+    }
+
+    /// The first jump whose condition holds.
+    fn resolve<'a>(&self, gotos: &'a [Goto]) -> Option<&'a str> {
+        gotos
+            .iter()
+            .find(|goto| self.holds(goto.condition.as_ref()))
+            .map(|goto| goto.node.as_str())
+    }
+}
+
+/// A conversation, loaded from a `.dialogue.ron` file.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct DialogueTree {
+    /// Where the conversation starts.
+    start: Vec<Goto>,
+    nodes: HashMap<String, DialogueNode>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DialogueNode {
+    #[serde(default)]
+    speaker: Option<String>,
+    text: String,
+    /// An emote to show above the speaker when the node is reached.
+    #[serde(default)]
+    emote: Option<EmoteKind>,
+    /// Flags to set when the node is reached.
+    #[serde(default)]
+    set: Vec<String>,
+    #[serde(default)]
+    choices: Vec<DialogueChoice>,
+    /// Where to go after this node if it has no choices. The conversation
+    /// ends if none apply.
+    #[serde(default)]
+    next: Vec<Goto>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DialogueChoice {
+    text: String,
+    /// Only offer this choice if the condition holds.
+    #[serde(default)]
+    condition: Option<Condition>,
+    /// Flags to set when the choice is picked.
+    #[serde(default)]
+    set: Vec<String>,
+    /// Where to go when the choice is picked. The conversation ends if none
+    /// apply.
+    #[serde(default)]
+    next: Vec<Goto>,
+}
+
+/// A jump to another node, taken only if its condition holds.
+#[derive(Deserialize, Debug)]
+struct Goto {
+    #[serde(default)]
+    condition: Option<Condition>,
+    node: String,
+}
+
+#[derive(Deserialize, Debug)]
+enum Condition {
+    Flag(String),
+    NotFlag(String),
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct DialogueAssets {
+    /// The pieces of the dialogue box, in rows from top left to bottom right.
+    #[dependency]
+    box_pieces: Vec<Handle<Image>>,
+}
+
+impl FromWorld for DialogueAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            box_pieces: ["tl", "tc", "tr", "lc", "c", "rc", "bl", "bc", "br"]
+                .into_iter()
+                .map(|piece| {
+                    load_pixel_art(
+                        assets,
+                        format!("UI/9slice_box_white/w_box_9slice_{piece}.png"),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The conversation that's currently running.
+#[derive(Resource, Debug)]
+pub struct ActiveDialogue {
+    tree: Handle<DialogueTree>,
+    /// Who the player is talking to.
+    pub speaker: Entity,
+    node: String,
+    /// How many characters of the node's text are shown.
+    revealed: usize,
+    typewriter: Timer,
+    /// The highlighted choice, among those whose conditions hold.
+    selected: usize,
+}
+
+impl ActiveDialogue {
+    fn enter(
+        &mut self,
+        commands: &mut Commands,
+        node: &str,
+        tree: &DialogueTree,
+        flags: &mut DialogueFlags,
+    ) {
+        self.node = node.to_owned();
+        self.revealed = 0;
+        self.selected = 0;
+        let Some(node) = tree.nodes.get(node) else {
+            return;
+        };
+        flags.set_all(&node.set);
+        if let Some(emote) = node.emote {
+            commands.trigger(Emote::new(self.speaker, emote));
+        }
+    }
+}
+
+/// Every game starts without any flags set.
+fn reset_flags(mut flags: ResMut<DialogueFlags>) {
+    flags.0.clear();
+}
+
+fn load_talk_dialogue(
+    mut commands: Commands,
+    talkers: Query<(Entity, &Talk), Without<TalkDialogue>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, talk) in &talkers {
+        commands
+            .entity(entity)
+            .insert(TalkDialogue(asset_server.load(talk.dialogue.clone())));
+    }
+}
+
+fn start_dialogue(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    talkers: Query<&TalkDialogue>,
+    trees: Res<Assets<DialogueTree>>,
+    mut flags: ResMut<DialogueFlags>,
+    dialogue_assets: Res<DialogueAssets>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    let Ok(TalkDialogue(handle)) = talkers.get(interacted.entity) else {
+        return;
+    };
+    let Some(tree) = trees.get(handle) else {
+        return;
+    };
+    let Some(start) = flags.resolve(&tree.start).map(str::to_owned) else {
+        return;
+    };
+
+    let mut dialogue = ActiveDialogue {
+        tree: handle.clone(),
+        speaker: interacted.entity,
+        node: String::new(),
+        revealed: 0,
+        typewriter: Timer::from_seconds(1.0 / TYPEWRITER_CHARS_PER_SEC, TimerMode::Repeating),
+        selected: 0,
+    };
+    dialogue.enter(&mut commands, &start, tree, &mut flags);
+    commands.insert_resource(dialogue);
+    commands.spawn(dialogue_box(&dialogue_assets));
+    next_pause.set(Pause(true));
+}
+
+fn end_dialogue(
+    mut commands: Commands,
+    boxes: Query<Entity, With<DialogueBox>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    commands.remove_resource::<ActiveDialogue>();
+    for entity in &boxes {
+        commands.entity(entity).despawn();
+    }
+    next_pause.set(Pause(false));
+}
+
+fn tick_typewriter(time: Res<Time>, mut dialogue: ResMut<ActiveDialogue>) {
+    let ticks = dialogue
+        .typewriter
+        .tick(time.delta())
+        .times_finished_this_tick();
+    dialogue.revealed += ticks as usize;
+}
+
+fn advance_dialogue(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    trees: Res<Assets<DialogueTree>>,
+    mut flags: ResMut<DialogueFlags>,
+    boxes: Query<Entity, With<DialogueBox>>,
+    next_pause: ResMut<NextState<Pause>>,
+) {
+    // Don't let the key press that started the conversation skip ahead.
+    if dialogue.is_added() {
+        return;
+    }
+    let Some(tree) = trees.get(&dialogue.tree) else {
+        return;
+    };
+    let Some(node) = tree.nodes.get(&dialogue.node) else {
+        end_dialogue(commands, boxes, next_pause);
+        return;
+    };
+
+    let text_len = node.text.chars().count();
+    let choices = node
+        .choices
+        .iter()
+        .filter(|choice| flags.holds(choice.condition.as_ref()))
+        .collect::<Vec<_>>();
+
+    if dialogue.revealed < text_len {
+        if input.any_just_pressed(ADVANCE_KEYS) {
+            dialogue.revealed = text_len;
+        }
+        return;
+    }
+
+    if !choices.is_empty() {
+        if input.any_just_pressed(PREVIOUS_CHOICE_KEYS) {
+            dialogue.selected = dialogue
+                .selected
+                .checked_sub(1)
+                .unwrap_or(choices.len() - 1);
+        }
+        if input.any_just_pressed(NEXT_CHOICE_KEYS) {
+            dialogue.selected = (dialogue.selected + 1) % choices.len();
+        }
+    }
+    if !input.any_just_pressed(ADVANCE_KEYS) {
+        return;
+    }
+
+    let next = match choices.get(dialogue.selected) {
+        Some(choice) => {
+            flags.set_all(&choice.set);
+            flags.resolve(&choice.next)
+        }
+        None => flags.resolve(&node.next),
+    };
+    match next {
+        Some(next) => {
+            let next = next.to_owned();
+            dialogue.enter(&mut commands, &next, tree, &mut flags);
+        }
+        None => end_dialogue(commands, boxes, next_pause),
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueBox;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueSpeakerName;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueText;
+
+/// Holds the choices, and remembers what they were built for so they're only
+/// rebuilt when something changes.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+struct DialogueChoices {
+    shown: Option<(String, usize)>,
+}
+
+fn dialogue_box(dialogue_assets: &DialogueAssets) -> impl Bundle {
+    let piece = BOX_PIECE_SIZE * BOX_SCALE;
+    let pieces = dialogue_assets.box_pieces.clone();
+    (
+        Name::new("Dialogue Box"),
+        DialogueBox,
+        Node {
+            position_type: PositionType::Absolute,
+            left: percent(10),
+            right: percent(10),
+            // Leave room for the hotbar.
+            bottom: px(80),
+            min_height: px(140),
+            ..default()
+        },
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (
+                Name::new("Dialogue Box Background"),
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: percent(100),
+                    height: percent(100),
+                    display: Display::Grid,
+                    grid_template_columns: vec![
+                        GridTrack::px(piece),
+                        GridTrack::flex(1.0),
+                        GridTrack::px(piece),
+                    ],
+                    grid_template_rows: vec![
+                        GridTrack::px(piece),
+                        GridTrack::flex(1.0),
+                        GridTrack::px(piece),
+                    ],
+                    ..default()
+                },
+                Children::spawn(SpawnIter(pieces.into_iter().map(|image| {
+                    (
+                        Name::new("Dialogue Box Piece"),
+                        ImageNode {
+                            image,
+                            image_mode: NodeImageMode::Stretch,
+                            ..default()
+                        },
+                    )
+                }))),
+            ),
+            (
+                Name::new("Dialogue Content"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    flex_grow: 1.0,
+                    row_gap: px(8),
+                    padding: UiRect::all(px(piece + 8.0)),
+                    ..default()
+                },
+                children![
+                    (
+                        Name::new("Dialogue Speaker"),
+                        DialogueSpeakerName,
+                        Text::default(),
+                        TextFont::from_font_size(20.0),
+                        TextColor(SPEAKER_TEXT),
+                    ),
+                    (
+                        Name::new("Dialogue Text"),
+                        DialogueText,
+                        Text::default(),
+                        TextFont::from_font_size(24.0),
+                        TextColor(DIALOGUE_TEXT),
+                    ),
+                    (
+                        Name::new("Dialogue Choices"),
+                        DialogueChoices::default(),
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: px(4),
+                            ..default()
+                        },
+                    ),
+                ],
+            ),
+        ],
+    )
+}
+
+fn update_dialogue_ui(
+    mut commands: Commands,
+    dialogue: Option<Res<ActiveDialogue>>,
+    trees: Res<Assets<DialogueTree>>,
+    flags: Res<DialogueFlags>,
+    mut speaker_name: Query<&mut Text, (With<DialogueSpeakerName>, Without<DialogueText>)>,
+    mut text: Query<&mut Text, (With<DialogueText>, Without<DialogueSpeakerName>)>,
+    mut choices: Query<(Entity, &mut DialogueChoices)>,
+) {
+    // The conversation may have just ended.
+    let Some(dialogue) = dialogue else {
+        return;
+    };
+    let Some(node) = trees
+        .get(&dialogue.tree)
+        .and_then(|tree| tree.nodes.get(&dialogue.node))
+    else {
+        return;
+    };
+
+    for mut speaker_name in &mut speaker_name {
+        let name = node.speaker.as_deref().unwrap_or_default();
+        if speaker_name.0 != name {
+            speaker_name.0 = name.to_owned();
+        }
+    }
+    for mut text in &mut text {
+        let shown = node
+            .text
+            .chars()
+            .take(dialogue.revealed)
+            .collect::<String>();
+        if text.0 != shown {
+            text.0 = shown;
+        }
+    }
+
+    // Only offer choices once the whole line is out.
+    let fully_revealed = dialogue.revealed >= node.text.chars().count();
+    let shown = fully_revealed.then(|| (dialogue.node.clone(), dialogue.selected));
+    for (entity, mut choices) in &mut choices {
+        if choices.shown == shown {
+            continue;
+        }
+        choices.shown = shown.clone();
+        commands.entity(entity).despawn_children();
+        if !fully_revealed {
+            continue;
+        }
+
+        let visible = node
+            .choices
+            .iter()
+            .filter(|choice| flags.holds(choice.condition.as_ref()));
+        for (index, choice) in visible.enumerate() {
+            let selected = index == dialogue.selected;
+            commands.spawn((
+                Name::new("Dialogue Choice"),
+                Text(if selected {
+                    format!("> {}", choice.text)
+                } else {
+                    format!("  {}", choice.text)
+                }),
+                TextFont::from_font_size(20.0),
+                TextColor(if selected {
+                    SELECTED_CHOICE_TEXT
+                } else {
+                    CHOICE_TEXT
+                }),
+                ChildOf(entity),
+            ));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Emote bubbles that pop up above characters, e.g. when an NPC spots the
+//! player or an animal is petted.
+//!
+//! Trigger an [`Emote`] on any entity with a transform to show one above it.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    AppSystems,
+    asset_tracking::{LoadResource, load_pixel_art},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<EmoteKind>()
+        .register_type::<EmoteBubble>();
+    app.load_resource::<EmoteAssets>();
+    app.add_observer(show_emote);
+
+    // Emotes keep bouncing while the game is paused, so that they also work
+    // during conversations.
+    app.add_systems(
+        Update,
+        (
+            tick_emote_bubbles.in_set(AppSystems::TickTimers),
+            bounce_emote_bubbles.in_set(AppSystems::Update),
+        ),
+    );
+}
+
+/// How long an emote stays up by default, in seconds.
+const DEFAULT_EMOTE_SECS: f32 = 1.5;
+
+/// How high above its character an emote appears.
+const EMOTE_HEIGHT: f32 = 14.0;
+
+/// How high an emote bounces when it pops up.
+const BOUNCE_HEIGHT: f32 = 4.0;
+
+/// How long a single bounce takes, in seconds.
+const BOUNCE_SECS: f32 = 0.25;
+
+/// How quickly the bouncing dies down. Higher is quicker.
+const BOUNCE_DAMPING: f32 = 4.0;
+
+/// Which icon an emote shows.
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmoteKind {
+    Alerted,
+    Chat,
+    Confused,
+    Happy,
+    Love,
+    Stress,
+    Working,
+}
+
+/// Triggered on a character to show an emote above it. Replaces the emote
+/// it's currently showing, if any.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Emote {
+    pub entity: Entity,
+    pub kind: EmoteKind,
+    /// How long the emote stays up, in seconds.
+    pub secs: f32,
+}
+
+impl Emote {
+    pub fn new(entity: Entity, kind: EmoteKind) -> Self {
+        Self {
+            entity,
+            kind,
+            secs: DEFAULT_EMOTE_SECS,
+        }
+    }
+}
+
+/// An emote icon floating above a character.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct EmoteBubble(Timer);
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct EmoteAssets {
+    /// One icon per [`EmoteKind`], in declaration order.
+    #[dependency]
+    icons: Vec<Handle<Image>>,
+}
+
+impl FromWorld for EmoteAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            icons: [
+                "UI/expression_alerted.png",
+                "UI/expression_chat.png",
+                "UI/expression_confused.png",
+                "UI/happiness_01.png",
+                "UI/expression_love.png",
+                "UI/expression_stress.png",
+                "UI/expression_working.png",
+            ]
+            .into_iter()
+            .map(|path| load_pixel_art(assets, path))
+            .collect(),
+        }
+    }
+}
+
+fn show_emote(
+    emote: On<Emote>,
+    mut commands: Commands,
+    characters: Query<Option<&Children>, With<Transform>>,
+    bubbles: Query<(), With<EmoteBubble>>,
+    emote_assets: Res<EmoteAssets>,
+) {
+    let Ok(children) = characters.get(emote.entity) else {
+        return;
+    };
+
+    // Only one emote at a time.
+    for &child in children.into_iter().flatten() {
+        if bubbles.contains(child) {
+            commands.entity(child).despawn();
+        }
+    }
+
+    commands.spawn((
+        Name::new("Emote"),
+        EmoteBubble(Timer::from_seconds(emote.secs, TimerMode::Once)),
+        Sprite::from_image(emote_assets.icons[emote.kind as usize].clone()),
+        Transform::from_xyz(0.0, EMOTE_HEIGHT, 2.0),
+        ChildOf(emote.entity),
+    ));
+}
+
+fn tick_emote_bubbles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut EmoteBubble)>,
+) {
+    for (entity, mut bubble) in &mut query {
+        if bubble.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Pops emotes up with a few bounces that die down.
+fn bounce_emote_bubbles(mut query: Query<(&EmoteBubble, &mut Transform)>) {
+    for (bubble, mut transform) in &mut query {
+        let elapsed = bubble.0.elapsed_secs();
+        let bounce = (elapsed / BOUNCE_SECS * PI).sin().abs() * (-elapsed * BOUNCE_DAMPING).exp();
+        transform.translation.y = EMOTE_HEIGHT + BOUNCE_HEIGHT * bounce;
+    }
+}
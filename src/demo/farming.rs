@@ -0,0 +1,416 @@
+//! Crop farming: till the soil, plant seeds, water them and harvest the crop.
+//!
+//! - With the shovel selected on the hotbar, press the use key while facing a
+//!   [`TillableTile`] to turn it into a [`FarmPlot`].
+//! - With seeds selected, use them on an empty plot to plant a [`Crop`].
+//! - With the watering can selected, use it on a plot to water it.
+//!
+//! Every day, crops on watered plots grow to their next stage and the plots
+//! dry out again. Once a crop is ripe, interact with it to harvest it.
+
+use avian2d::prelude::{SpatialQuery, SpatialQueryFilter};
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::{LoadResource, load_pixel_art},
+    demo::{
+        animation::{CharacterAnimation, CharacterAnimationState},
+        carry::Carrying,
+        clock::DayStarted,
+        combat::Attacking,
+        fishing::Fishing,
+        health::{Dead, Hit},
+        hotbar::Hotbar,
+        interaction::{Interactable, Interacted},
+        inventory::{Inventory, ItemAssets, ItemCatalog, ItemCategory},
+        level::TILE_SIZE,
+        movement::MovementController,
+        player::Player,
+        tiles::MapTiles,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TillableTile>()
+        .register_type::<FarmPlot>()
+        .register_type::<Crop>()
+        .register_type::<Working>();
+    app.load_resource::<FarmingAssets>();
+
+    app.add_systems(
+        Update,
+        (
+            use_item
+                .run_if(input_just_pressed(USE_ITEM_KEY))
+                .in_set(AppSystems::RecordInput),
+            (advance_work, update_plot_sprites)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(grow_crops);
+    app.add_observer(harvest_crop);
+    app.add_observer(interrupt_work_on_hit);
+}
+
+const USE_ITEM_KEY: KeyCode = KeyCode::KeyQ;
+
+/// The item id of the tool that tills the soil.
+const SHOVEL: &str = "shovel";
+
+/// The item id of the tool that waters plots.
+const WATERING_CAN: &str = "watering_can";
+
+/// The growth stage at which a crop can be harvested. Its sprites go from
+/// `_00` up to this stage, and the `_05` sprite is the icon of the harvested
+/// item.
+const RIPE_STAGE: u8 = 4;
+
+/// How close the player needs to be to a ripe crop to harvest it.
+const HARVEST_RANGE: f32 = 20.0;
+
+/// How far above the center of its plot a crop is drawn, so that it appears
+/// to stand in the soil.
+const CROP_OFFSET: f32 = 3.0;
+
+/// A tile that can be tilled with the shovel. Set this as a property on tiles
+/// in the tileset.
+#[derive(Component, Default, Debug, Reflect, PartialEq, Eq, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct TillableTile;
+
+/// A tilled tile that seeds can be planted in.
+#[derive(Component, Serialize, Deserialize, Default, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct FarmPlot {
+    /// Whether the plot was watered today.
+    pub watered: bool,
+}
+
+/// A crop growing on a [`FarmPlot`].
+#[derive(Component, Serialize, Deserialize, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Crop {
+    /// The item id of what's growing, e.g. `carrot`.
+    pub kind: String,
+    pub stage: u8,
+}
+
+impl Crop {
+    pub fn is_ripe(&self) -> bool {
+        self.stage >= RIPE_STAGE
+    }
+
+    fn image_path(&self) -> String {
+        format!("Elements/Crops/{}_{:02}.png", self.kind, self.stage)
+    }
+}
+
+/// The sprite child of a [`FarmPlot`] that shows its crop.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CropSprite;
+
+#[derive(Debug, Clone, Reflect)]
+enum FarmAction {
+    Till(Vec2),
+    Water(Entity),
+    Plant { plot: Entity, seeds: String },
+    Harvest(Entity),
+}
+
+/// Present on a character while they're working the soil. The action takes
+/// effect once the animation is done.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Working {
+    action: FarmAction,
+}
+
+impl Working {
+    pub fn animation_state(&self) -> CharacterAnimationState {
+        match self.action {
+            FarmAction::Till(_) => CharacterAnimationState::Digging,
+            FarmAction::Water(_) => CharacterAnimationState::Watering,
+            FarmAction::Plant { .. } | FarmAction::Harvest(_) => CharacterAnimationState::Doing,
+        }
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct FarmingAssets {
+    #[dependency]
+    dry_soil: Handle<Image>,
+    #[dependency]
+    wet_soil: Handle<Image>,
+}
+
+impl FromWorld for FarmingAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            dry_soil: load_pixel_art(assets, "Elements/Crops/soil_00.png"),
+            wet_soil: load_pixel_art(assets, "Elements/Crops/soil_01.png"),
+        }
+    }
+}
+
+/// Uses the item selected on the hotbar on the tile in front of the player.
+fn use_item(
+    mut commands: Commands,
+    player: Single<
+        (Entity, &GlobalTransform, &MovementController, &Inventory),
+        (
+            With<Player>,
+            Without<Working>,
+            Without<Attacking>,
+            Without<Dead>,
+            Without<Fishing>,
+            Without<Carrying>,
+        ),
+    >,
+    hotbar: Res<Hotbar>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+    plots: Query<(Entity, &FarmPlot, &GlobalTransform, Has<Crop>)>,
+    map_tiles: MapTiles,
+    tillable_tiles: Query<(), With<TillableTile>>,
+    spatial_query: SpatialQuery,
+) {
+    let (player, transform, controller, inventory) = player.into_inner();
+    let (Some(stack), Some(catalog)) = (
+        hotbar.selected_stack(inventory),
+        catalogs.get(&item_assets.catalog),
+    ) else {
+        return;
+    };
+    let Some(item) = catalog.get(&stack.item) else {
+        return;
+    };
+
+    let Some(tile) = map_tiles.tile_center(
+        transform.translation().truncate() + controller.facing.normalize_or_zero() * TILE_SIZE,
+    ) else {
+        return;
+    };
+    let plot = plots.iter().find(|(_, _, transform, _)| {
+        map_tiles.tile_center(transform.translation().truncate()) == Some(tile)
+    });
+
+    let action = match (stack.item.as_str(), item.category, plot) {
+        (SHOVEL, _, None) => {
+            let tillable = map_tiles
+                .tiles_at(tile)
+                .any(|tile| tillable_tiles.contains(tile));
+            let filter = SpatialQueryFilter::default().with_excluded_entities([player]);
+            let blocked = !spatial_query.point_intersections(tile, &filter).is_empty();
+            if !tillable || blocked {
+                return;
+            }
+            FarmAction::Till(tile)
+        }
+        (WATERING_CAN, _, Some((plot, farm_plot, ..))) if !farm_plot.watered => {
+            FarmAction::Water(plot)
+        }
+        (seeds, ItemCategory::Seed, Some((plot, _, _, false))) => FarmAction::Plant {
+            plot,
+            seeds: seeds.to_owned(),
+        },
+        _ => return,
+    };
+    commands.entity(player).insert(Working { action });
+}
+
+/// Harvests a ripe crop when the player interacts with it.
+fn harvest_crop(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    crops: Query<&Crop>,
+    workers: Query<
+        (),
+        (
+            Without<Working>,
+            Without<Attacking>,
+            Without<Fishing>,
+            Without<Carrying>,
+        ),
+    >,
+) {
+    if crops.get(interacted.entity).is_ok_and(Crop::is_ripe)
+        && workers.contains(interacted.interactor)
+    {
+        commands.entity(interacted.interactor).insert(Working {
+            action: FarmAction::Harvest(interacted.entity),
+        });
+    }
+}
+
+/// Holds workers in place, and carries out their action once the animation
+/// is done.
+fn advance_work(
+    mut commands: Commands,
+    mut workers: Query<(
+        Entity,
+        &Working,
+        &GlobalTransform,
+        &mut MovementController,
+        &CharacterAnimation,
+        &mut Inventory,
+    )>,
+    mut plots: Query<(&mut FarmPlot, Option<&Crop>)>,
+    farming_assets: If<Res<FarmingAssets>>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+) {
+    for (entity, working, transform, mut controller, animation, mut inventory) in &mut workers {
+        controller.intent = Vec2::ZERO;
+        if animation.state() != working.animation_state() || !animation.is_finished() {
+            continue;
+        }
+        commands.entity(entity).remove::<Working>();
+
+        match &working.action {
+            &FarmAction::Till(tile) => {
+                // Lie flat on the ground, below the characters walking over it.
+                let z = transform.translation().z - 2.0;
+                commands.spawn(farm_plot(
+                    tile.extend(z),
+                    FarmPlot::default(),
+                    &farming_assets,
+                ));
+            }
+            &FarmAction::Water(plot) => {
+                if let Ok((mut farm_plot, _)) = plots.get_mut(plot) {
+                    farm_plot.watered = true;
+                }
+            }
+            FarmAction::Plant { plot, seeds } => {
+                let Some(kind) = seeds.strip_suffix("_seeds") else {
+                    continue;
+                };
+                if plots.get(*plot).is_ok_and(|(_, crop)| crop.is_none())
+                    && inventory.remove(seeds, 1)
+                {
+                    commands.entity(*plot).insert(Crop {
+                        kind: kind.to_owned(),
+                        stage: 0,
+                    });
+                }
+            }
+            &FarmAction::Harvest(plot) => {
+                let (Ok((_, Some(crop))), Some(catalog)) =
+                    (plots.get(plot), catalogs.get(&item_assets.catalog))
+                else {
+                    continue;
+                };
+                if crop.is_ripe() && inventory.add(catalog, &crop.kind, 1) == 0 {
+                    commands.entity(plot).remove::<(Crop, Interactable)>();
+                }
+            }
+        }
+    }
+}
+
+fn farm_plot(translation: Vec3, plot: FarmPlot, farming_assets: &FarmingAssets) -> impl Bundle {
+    (
+        Name::new("Farm Plot"),
+        plot,
+        Sprite::from_image(farming_assets.dry_soil.clone()),
+        Transform::from_translation(translation),
+        DespawnOnExit(Screen::Gameplay),
+        children![(
+            Name::new("Crop"),
+            CropSprite,
+            Sprite::default(),
+            Transform::from_xyz(0.0, CROP_OFFSET, 0.1),
+            Visibility::Hidden,
+        )],
+    )
+}
+
+/// Puts a plot back into the world, along with the crop growing on it, e.g.
+/// when loading a save.
+pub fn spawn_saved_plot(
+    commands: &mut Commands,
+    translation: Vec3,
+    plot: FarmPlot,
+    crop: Option<Crop>,
+    farming_assets: &FarmingAssets,
+) {
+    let mut entity = commands.spawn(farm_plot(translation, plot, farming_assets));
+    if let Some(crop) = crop {
+        if crop.is_ripe() {
+            entity.insert(Interactable::new("Harvest", HARVEST_RANGE));
+        }
+        entity.insert(crop);
+    }
+}
+
+/// Starts a new day: crops on watered plots grow, and all plots dry out.
+fn grow_crops(
+    _: On<DayStarted>,
+    mut commands: Commands,
+    mut plots: Query<(Entity, &mut FarmPlot, Option<&mut Crop>)>,
+) {
+    for (entity, mut plot, crop) in &mut plots {
+        if let Some(mut crop) = crop
+            && plot.watered
+            && !crop.is_ripe()
+        {
+            crop.stage += 1;
+            if crop.is_ripe() {
+                commands
+                    .entity(entity)
+                    .insert(Interactable::new("Harvest", HARVEST_RANGE));
+            }
+        }
+        plot.watered = false;
+    }
+}
+
+fn update_plot_sprites(
+    mut plots: Query<
+        (Ref<FarmPlot>, Option<Ref<Crop>>, &mut Sprite, &Children),
+        Without<CropSprite>,
+    >,
+    mut crop_sprites: Query<(&mut Sprite, &mut Visibility), With<CropSprite>>,
+    farming_assets: If<Res<FarmingAssets>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (plot, crop, mut sprite, children) in &mut plots {
+        if plot.is_changed() {
+            sprite.image = if plot.watered {
+                farming_assets.wet_soil.clone()
+            } else {
+                farming_assets.dry_soil.clone()
+            };
+        }
+
+        let mut crop_sprites = crop_sprites.iter_many_mut(children);
+        while let Some((mut crop_sprite, mut visibility)) = crop_sprites.fetch_next() {
+            match &crop {
+                Some(crop) if crop.is_changed() => {
+                    crop_sprite.image = load_pixel_art(&asset_server, crop.image_path());
+                    *visibility = Visibility::Inherited;
+                }
+                Some(_) => {}
+                None => {
+                    visibility.set_if_neq(Visibility::Hidden);
+                }
+            }
+        }
+    }
+}
+
+/// Getting hit interrupts whatever the character was doing.
+fn interrupt_work_on_hit(hit: On<Hit>, mut commands: Commands, working: Query<(), With<Working>>) {
+    if working.contains(hit.entity) {
+        commands.entity(hit.entity).remove::<Working>();
+    }
+}
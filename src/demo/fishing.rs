@@ -0,0 +1,588 @@
+//! Fishing: cast a line into the water, wait for a bite and reel the fish in.
+//!
+//! - Press the fish key while facing a [`WaterTile`] to cast.
+//! - After a random delay a fish bites. Press the key again before it lets go
+//!   to hook it.
+//! - While reeling, press the key when the marker is inside the target zone to
+//!   fill the catch meter. Missing drains it, and the fish escapes once it's
+//!   empty.
+//!
+//! What can be caught is configured per map in `data/fish_tables.fish.ron`.
+
+use std::collections::HashMap;
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy_ecs_tiled::prelude::*;
+use rand::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::{LoadResource, load_pixel_art},
+    data::LoadRonAsset,
+    demo::{
+        animation::{CharacterAnimation, CharacterAnimationState},
+        carry::Carrying,
+        combat::Attacking,
+        health::Hit,
+        inventory::{Inventory, ItemAssets, ItemCatalog},
+        level::TILE_SIZE,
+        movement::MovementController,
+        player::Player,
+        tiles::MapTiles,
+    },
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<WaterTile>();
+    app.init_ron_asset::<FishTables>("fish.ron");
+    app.load_resource::<FishingAssets>();
+
+    app.add_systems(
+        Update,
+        (
+            tick_fishing.in_set(AppSystems::TickTimers),
+            press_fish_key
+                .run_if(input_just_pressed(FISH_KEY))
+                .in_set(AppSystems::RecordInput),
+            (
+                cancel_fishing_on_move,
+                advance_fishing,
+                sync_fishing_ui,
+                update_reel_ui,
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(store_catch);
+    app.add_observer(stop_fishing_on_hit);
+}
+
+const FISH_KEY: KeyCode = KeyCode::KeyF;
+
+/// How far in front of the player the line lands.
+const CAST_DISTANCE: f32 = 2.0 * TILE_SIZE;
+
+/// How long to wait for a bite, in seconds.
+const BITE_DELAY_SECS: std::ops::Range<f32> = 2.0..6.0;
+
+/// How long a fish nibbles before letting go, in seconds.
+const BITE_WINDOW_SECS: f32 = 1.0;
+
+/// Width of the reel target zone, as a fraction of the bar.
+const REEL_ZONE_WIDTH: f32 = 0.2;
+
+/// How full the catch meter starts out.
+const REEL_START_PROGRESS: f32 = 0.3;
+
+/// How much a well-timed press fills the catch meter.
+const REEL_HIT_PROGRESS: f32 = 0.35;
+
+/// How much a badly-timed press drains the catch meter.
+const REEL_MISS_PENALTY: f32 = 0.3;
+
+const REEL_BAR_BACKGROUND: Color = Color::srgb(0.157, 0.157, 0.157);
+const REEL_ZONE_COLOR: Color = Color::srgb(0.361, 0.702, 0.341);
+const REEL_MARKER_COLOR: Color = Color::srgb(0.925, 0.925, 0.925);
+const REEL_PROGRESS_COLOR: Color = Color::srgb(0.275, 0.400, 0.750);
+
+/// A tile that can be fished in. Set this as a property on water tiles in
+/// the tileset.
+#[derive(Component, Default, Debug, Reflect, PartialEq, Eq, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct WaterTile;
+
+/// Fish tables for every map.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct FishTables {
+    /// Used for maps that don't have their own table.
+    default: Vec<FishEntry>,
+    /// Keyed by map file name, e.g. `sample.tmx`.
+    maps: HashMap<String, Vec<FishEntry>>,
+}
+
+impl FishTables {
+    fn for_map(&self, map: Option<&str>) -> &[FishEntry] {
+        map.and_then(|map| self.maps.get(map))
+            .unwrap_or(&self.default)
+    }
+}
+
+/// Something that can be caught.
+#[derive(Deserialize, Reflect, Clone, Debug)]
+pub struct FishEntry {
+    /// The item id of the catch.
+    pub item: String,
+    /// How likely this catch is relative to the others in the table.
+    pub weight: u32,
+    /// How fast the reel marker moves, in bar lengths per second.
+    pub speed: f32,
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct FishingAssets {
+    #[dependency]
+    tables: Handle<FishTables>,
+    #[dependency]
+    rod: Handle<Image>,
+}
+
+impl FromWorld for FishingAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            tables: assets.load("data/fish_tables.fish.ron"),
+            rod: load_pixel_art(assets, "UI/rod.png"),
+        }
+    }
+}
+
+/// Triggered on the angler when they land a catch.
+#[derive(EntityEvent, Debug)]
+pub struct FishCaught {
+    pub entity: Entity,
+    pub item: String,
+}
+
+/// Present on a character while they're fishing.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Fishing {
+    state: FishingState,
+    /// What's on the other end of the line, decided when casting.
+    catch: FishEntry,
+}
+
+#[derive(Debug, Reflect)]
+enum FishingState {
+    /// Playing the casting animation.
+    Casting,
+    /// Waiting for a bite.
+    Waiting(Timer),
+    /// A fish is nibbling. It lets go when the timer runs out.
+    Bite(Timer),
+    /// Playing the reeling minigame.
+    Reeling(ReelMinigame),
+    /// Showing off the catch.
+    Caught,
+}
+
+impl Fishing {
+    /// The animation the angler should play.
+    pub fn animation_state(&self) -> CharacterAnimationState {
+        match self.state {
+            FishingState::Casting => CharacterAnimationState::Casting,
+            FishingState::Waiting(_) | FishingState::Bite(_) => CharacterAnimationState::Waiting,
+            FishingState::Reeling(_) => CharacterAnimationState::Reeling,
+            FishingState::Caught => CharacterAnimationState::Caught,
+        }
+    }
+}
+
+#[derive(Debug, Reflect)]
+struct ReelMinigame {
+    /// Position of the marker along the bar, from 0 to 1.
+    marker: f32,
+    /// Direction the marker is moving in, either 1 or -1.
+    direction: f32,
+    /// How fast the marker moves, in bar lengths per second.
+    speed: f32,
+    /// Start of the target zone along the bar, from 0 to 1.
+    zone_start: f32,
+    /// How full the catch meter is, from 0 to 1.
+    progress: f32,
+}
+
+impl ReelMinigame {
+    fn new(speed: f32) -> Self {
+        Self {
+            marker: 0.0,
+            direction: 1.0,
+            speed,
+            zone_start: random_zone_start(),
+            progress: REEL_START_PROGRESS,
+        }
+    }
+
+    /// Move the marker back and forth along the bar.
+    fn tick(&mut self, delta_secs: f32) {
+        self.marker += self.direction * self.speed * delta_secs;
+        if self.marker >= 1.0 {
+            self.marker = 2.0 - self.marker;
+            self.direction = -1.0;
+        } else if self.marker <= 0.0 {
+            self.marker = -self.marker;
+            self.direction = 1.0;
+        }
+    }
+
+    /// Handle a press of the fish key.
+    fn reel(&mut self) {
+        let in_zone = (self.zone_start..=self.zone_start + REEL_ZONE_WIDTH).contains(&self.marker);
+        if in_zone {
+            self.progress += REEL_HIT_PROGRESS;
+            self.zone_start = random_zone_start();
+        } else {
+            self.progress -= REEL_MISS_PENALTY;
+        }
+    }
+}
+
+fn random_zone_start() -> f32 {
+    rand::rng().random_range(0.0..1.0 - REEL_ZONE_WIDTH)
+}
+
+fn tick_fishing(time: Res<Time>, mut query: Query<&mut Fishing>) {
+    for mut fishing in &mut query {
+        match &mut fishing.state {
+            FishingState::Waiting(timer) | FishingState::Bite(timer) => {
+                timer.tick(time.delta());
+            }
+            FishingState::Reeling(minigame) => minigame.tick(time.delta_secs()),
+            FishingState::Casting | FishingState::Caught => {}
+        }
+    }
+}
+
+/// Cast, hook or reel depending on how far along the player is.
+fn press_fish_key(
+    mut commands: Commands,
+    player: Single<
+        (
+            Entity,
+            &GlobalTransform,
+            &MovementController,
+            Option<&mut Fishing>,
+            Has<Carrying>,
+        ),
+        (With<Player>, Without<Attacking>),
+    >,
+    map_tiles: MapTiles,
+    water_tiles: Query<(), With<WaterTile>>,
+    ancestors: Query<&ChildOf>,
+    maps: Query<&TiledMap>,
+    asset_server: Res<AssetServer>,
+    fishing_assets: If<Res<FishingAssets>>,
+    fish_tables: Res<Assets<FishTables>>,
+) {
+    let (player, transform, controller, fishing, carrying) = player.into_inner();
+
+    if let Some(mut fishing) = fishing {
+        let speed = fishing.catch.speed;
+        match &mut fishing.state {
+            state @ FishingState::Bite(_) => {
+                *state = FishingState::Reeling(ReelMinigame::new(speed));
+            }
+            FishingState::Reeling(minigame) => minigame.reel(),
+            FishingState::Casting | FishingState::Waiting(_) | FishingState::Caught => {}
+        }
+        return;
+    }
+
+    if carrying {
+        return;
+    }
+
+    let target =
+        transform.translation().truncate() + controller.facing.normalize_or_zero() * CAST_DISTANCE;
+    let Some(water_tile) = map_tiles
+        .tiles_at(target)
+        .find(|&tile| water_tiles.contains(tile))
+    else {
+        return;
+    };
+
+    // Use the fish table of the map the water belongs to.
+    let map_name = ancestors
+        .iter_ancestors(water_tile)
+        .find_map(|ancestor| maps.get(ancestor).ok())
+        .and_then(|map| asset_server.get_path(map.0.id()))
+        .and_then(|path| {
+            path.path()
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        });
+    let Some(tables) = fish_tables.get(&fishing_assets.tables) else {
+        return;
+    };
+    let Ok(catch) = tables
+        .for_map(map_name.as_deref())
+        .choose_weighted(&mut rand::rng(), |entry| entry.weight)
+    else {
+        return;
+    };
+
+    commands.entity(player).insert(Fishing {
+        state: FishingState::Casting,
+        catch: catch.clone(),
+    });
+}
+
+/// Walking away reels the line back in empty-handed.
+fn cancel_fishing_on_move(
+    mut commands: Commands,
+    query: Query<(Entity, &MovementController, &Fishing)>,
+) {
+    for (entity, controller, fishing) in &query {
+        if controller.intent != Vec2::ZERO && !matches!(fishing.state, FishingState::Caught) {
+            commands.entity(entity).remove::<Fishing>();
+        }
+    }
+}
+
+fn advance_fishing(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Fishing, &CharacterAnimation)>,
+) {
+    for (entity, mut fishing, animation) in &mut query {
+        match &fishing.state {
+            FishingState::Casting => {
+                if animation.state() == CharacterAnimationState::Casting && animation.is_finished()
+                {
+                    let delay = rand::rng().random_range(BITE_DELAY_SECS);
+                    fishing.state =
+                        FishingState::Waiting(Timer::from_seconds(delay, TimerMode::Once));
+                }
+            }
+            FishingState::Waiting(timer) => {
+                if timer.is_finished() {
+                    fishing.state =
+                        FishingState::Bite(Timer::from_seconds(BITE_WINDOW_SECS, TimerMode::Once));
+                }
+            }
+            FishingState::Bite(timer) => {
+                if timer.is_finished() {
+                    info!("The fish got away");
+                    commands.entity(entity).remove::<Fishing>();
+                }
+            }
+            FishingState::Reeling(minigame) => {
+                if minigame.progress >= 1.0 {
+                    fishing.state = FishingState::Caught;
+                } else if minigame.progress <= 0.0 {
+                    info!("The fish got away");
+                    commands.entity(entity).remove::<Fishing>();
+                }
+            }
+            FishingState::Caught => {
+                if animation.state() == CharacterAnimationState::Caught && animation.is_finished() {
+                    commands.entity(entity).remove::<Fishing>();
+                    commands.trigger(FishCaught {
+                        entity,
+                        item: fishing.catch.item.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Getting hit makes the angler drop the rod.
+fn stop_fishing_on_hit(hit: On<Hit>, mut commands: Commands, fishing: Query<(), With<Fishing>>) {
+    if fishing.contains(hit.entity) {
+        commands.entity(hit.entity).remove::<Fishing>();
+    }
+}
+
+/// Puts the catch in the angler's inventory.
+fn store_catch(
+    caught: On<FishCaught>,
+    mut inventories: Query<&mut Inventory>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+) {
+    let (Ok(mut inventory), Some(catalog)) = (
+        inventories.get_mut(caught.entity),
+        catalogs.get(&item_assets.catalog),
+    ) else {
+        return;
+    };
+    if inventory.add(catalog, &caught.item, 1) > 0 {
+        info!("No room for {}, letting it go", caught.item);
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum FishingUi {
+    Bite,
+    Reel,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReelZone;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReelMarker;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReelProgress;
+
+/// Show the bite prompt or the reel minigame while the player is in those states.
+fn sync_fishing_ui(
+    mut commands: Commands,
+    fishing: Query<&Fishing, With<Player>>,
+    ui: Query<(Entity, &FishingUi)>,
+    fishing_assets: If<Res<FishingAssets>>,
+) {
+    let wanted = fishing
+        .single()
+        .ok()
+        .and_then(|fishing| match fishing.state {
+            FishingState::Bite(_) => Some(FishingUi::Bite),
+            FishingState::Reeling(_) => Some(FishingUi::Reel),
+            _ => None,
+        });
+
+    let mut present = false;
+    for (entity, kind) in &ui {
+        if Some(*kind) == wanted {
+            present = true;
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    match wanted {
+        Some(FishingUi::Bite) if !present => {
+            commands.spawn((
+                fishing_ui_root(FishingUi::Bite),
+                children![widget::header("Bite!")],
+            ));
+        }
+        Some(FishingUi::Reel) if !present => {
+            commands.spawn((
+                fishing_ui_root(FishingUi::Reel),
+                children![
+                    (
+                        Name::new("Rod Icon"),
+                        ImageNode::new(fishing_assets.rod.clone()),
+                        Node {
+                            width: px(48),
+                            height: px(48),
+                            ..default()
+                        },
+                    ),
+                    reel_bars(),
+                ],
+            ));
+        }
+        _ => {}
+    }
+}
+
+fn fishing_ui_root(kind: FishingUi) -> impl Bundle {
+    (
+        Name::new("Fishing UI"),
+        kind,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(60),
+            width: percent(100),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            column_gap: px(12),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+fn reel_bars() -> impl Bundle {
+    (
+        Name::new("Reel Bars"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: px(6),
+            ..default()
+        },
+        children![
+            (
+                Name::new("Reel Bar"),
+                Node {
+                    width: px(300),
+                    height: px(24),
+                    ..default()
+                },
+                BackgroundColor(REEL_BAR_BACKGROUND),
+                children![
+                    (
+                        Name::new("Reel Zone"),
+                        ReelZone,
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: percent(100.0 * REEL_ZONE_WIDTH),
+                            height: percent(100),
+                            ..default()
+                        },
+                        BackgroundColor(REEL_ZONE_COLOR),
+                    ),
+                    (
+                        Name::new("Reel Marker"),
+                        ReelMarker,
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: px(4),
+                            height: percent(100),
+                            ..default()
+                        },
+                        BackgroundColor(REEL_MARKER_COLOR),
+                    ),
+                ],
+            ),
+            (
+                Name::new("Catch Meter"),
+                Node {
+                    width: px(300),
+                    height: px(8),
+                    ..default()
+                },
+                BackgroundColor(REEL_BAR_BACKGROUND),
+                children![(
+                    Name::new("Catch Meter Fill"),
+                    ReelProgress,
+                    Node {
+                        height: percent(100),
+                        ..default()
+                    },
+                    BackgroundColor(REEL_PROGRESS_COLOR),
+                )],
+            ),
+        ],
+    )
+}
+
+fn update_reel_ui(
+    fishing: Query<&Fishing, With<Player>>,
+    mut zone: Query<&mut Node, (With<ReelZone>, Without<ReelMarker>, Without<ReelProgress>)>,
+    mut marker: Query<&mut Node, (With<ReelMarker>, Without<ReelZone>, Without<ReelProgress>)>,
+    mut progress: Query<&mut Node, (With<ReelProgress>, Without<ReelZone>, Without<ReelMarker>)>,
+) {
+    let Ok(Fishing {
+        state: FishingState::Reeling(minigame),
+        ..
+    }) = fishing.single()
+    else {
+        return;
+    };
+
+    for mut node in &mut zone {
+        node.left = percent(100.0 * minigame.zone_start);
+    }
+    for mut node in &mut marker {
+        node.left = percent(100.0 * minigame.marker);
+    }
+    for mut node in &mut progress {
+        node.width = percent(100.0 * minigame.progress.clamp(0.0, 1.0));
+    }
+}
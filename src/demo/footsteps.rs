@@ -0,0 +1,177 @@
+//! Footstep sounds that depend on what the character is walking on.
+//!
+//! Give tiles a [`Surface`] property in Tiled to pick their footsteps. Tiles
+//! without one fall back to the [`Surface`] of their map, then to grass.
+//! [`WaterTile`]s always sound like water.
+
+use std::collections::HashMap;
+
+use bevy::{audio::Volume, prelude::*};
+use rand::{prelude::*, rngs::ThreadRng};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{voices::SoundPriority, world_sound_effect},
+    demo::{
+        animation::{CharacterAnimation, CharacterAnimationState},
+        fishing::WaterTile,
+        npc::Npc,
+        player::Player,
+        tiles::MapTiles,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Surface>();
+    app.load_resource::<FootstepAssets>();
+
+    app.add_systems(
+        Update,
+        trigger_footsteps
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// How much the pitch of a footstep varies, relative to its set's pitch.
+const PITCH_VARIATION: f32 = 0.08;
+
+/// How much the volume of a footstep varies, relative to its set's volume.
+const VOLUME_VARIATION: f32 = 0.15;
+
+/// What a tile or map sounds like to walk on.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component, Default)]
+pub enum Surface {
+    #[default]
+    Grass,
+    Stone,
+    Wood,
+    Sand,
+    Water,
+}
+
+/// The step sounds for one surface, and how they are played.
+#[derive(Clone, Debug, Reflect)]
+pub struct FootstepSet {
+    sounds: Vec<Handle<AudioSource>>,
+    volume: f32,
+    pitch: f32,
+}
+
+impl FootstepSet {
+    /// A random step from the set, at a slightly random volume and pitch.
+    fn sound(&self, rng: &mut ThreadRng) -> Option<impl Bundle + use<>> {
+        let sound = self.sounds.choose(rng)?.clone();
+        let volume = self.volume * (1.0 + rng.random_range(-VOLUME_VARIATION..=VOLUME_VARIATION));
+        let pitch = self.pitch * (1.0 + rng.random_range(-PITCH_VARIATION..=PITCH_VARIATION));
+        Some(world_sound_effect(
+            sound,
+            PlaybackSettings::DESPAWN
+                .with_volume(Volume::Linear(volume))
+                .with_speed(pitch),
+        ))
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct FootstepAssets {
+    #[dependency]
+    steps: Vec<Handle<AudioSource>>,
+    player: HashMap<Surface, FootstepSet>,
+    npc: HashMap<Surface, FootstepSet>,
+}
+
+impl FromWorld for FootstepAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        // There is only one recording of steps so far, so the surfaces differ
+        // in volume and pitch. NPCs have heavier, quieter steps than the player.
+        let steps: Vec<Handle<AudioSource>> = (1..=4)
+            .map(|i| assets.load(format!("audio/sound_effects/step{i}.ogg")))
+            .collect();
+        let sets = |volume: f32, pitch: f32| {
+            HashMap::from_iter(
+                [
+                    (Surface::Grass, 0.8, 1.0),
+                    (Surface::Stone, 1.0, 1.15),
+                    (Surface::Wood, 1.0, 0.9),
+                    (Surface::Sand, 0.6, 0.85),
+                    (Surface::Water, 0.9, 1.3),
+                ]
+                .map(|(surface, surface_volume, surface_pitch)| {
+                    (
+                        surface,
+                        FootstepSet {
+                            sounds: steps.clone(),
+                            volume: volume * surface_volume,
+                            pitch: pitch * surface_pitch,
+                        },
+                    )
+                }),
+            )
+        };
+        Self {
+            player: sets(1.0, 1.0),
+            npc: sets(0.8, 0.8),
+            steps,
+        }
+    }
+}
+
+/// Finds the surface at `world_pos`, from the topmost tile that has one.
+fn surface_at(
+    world_pos: Vec2,
+    map_tiles: &MapTiles,
+    surfaces: &Query<(Option<&Surface>, Has<WaterTile>)>,
+) -> Surface {
+    map_tiles
+        .tiles_at(world_pos)
+        .find_map(|tile| match surfaces.get(tile) {
+            Ok((_, true)) => Some(Surface::Water),
+            Ok((surface, false)) => surface.copied(),
+            Err(_) => None,
+        })
+        .or_else(|| {
+            let map = map_tiles.map_at(world_pos)?;
+            surfaces.get(map).ok()?.0.copied()
+        })
+        .unwrap_or_default()
+}
+
+/// Plays a footstep, synchronized with the walking animation, for the surface
+/// under each walking character.
+fn trigger_footsteps(
+    mut commands: Commands,
+    footstep_assets: If<Res<FootstepAssets>>,
+    map_tiles: MapTiles,
+    surfaces: Query<(Option<&Surface>, Has<WaterTile>)>,
+    walkers: Query<
+        (Entity, &CharacterAnimation, &GlobalTransform, Has<Npc>),
+        Or<(With<Player>, With<Npc>)>,
+    >,
+) {
+    let rng = &mut rand::rng();
+    for (entity, animation, transform, is_npc) in &walkers {
+        if !(matches!(
+            animation.state(),
+            CharacterAnimationState::Walking | CharacterAnimationState::CarryWalking
+        ) && animation.changed()
+            && (animation.frame == 2 || animation.frame == 5))
+        {
+            continue;
+        }
+        let surface = surface_at(transform.translation().truncate(), &map_tiles, &surfaces);
+        // NPC steps are the first to go when too many sounds are playing.
+        let (sets, priority) = if is_npc {
+            (&footstep_assets.npc, SoundPriority::LOW)
+        } else {
+            (&footstep_assets.player, SoundPriority::NORMAL)
+        };
+        if let Some(sound) = sets.get(&surface).and_then(|set| set.sound(rng)) {
+            commands.entity(entity).with_child((sound, priority));
+        }
+    }
+}
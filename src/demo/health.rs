@@ -0,0 +1,158 @@
+//! Health, damage and death for any actor.
+//!
+//! Trigger [`Damage`] or [`Heal`] on an entity with [`Health`] to change it.
+//! Damage triggers [`Hit`], knocks the target back, briefly makes it
+//! [`Invulnerable`], and [`Died`] is triggered once its health runs out.
+
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::movement::{Knockback, MovementController},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(apply_damage);
+    app.add_observer(apply_heal);
+
+    app.add_systems(
+        Update,
+        tick_hurt_and_invulnerability
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems),
+    );
+}
+
+/// How long an actor can't be damaged again after taking a hit, in seconds.
+const INVULNERABILITY_SECS: f32 = 1.0;
+
+/// How long an actor reels from a hit, in seconds. Matches the hurt animation.
+const HURT_SECS: f32 = 0.4;
+
+/// How long a hit pushes an actor around, in seconds.
+const KNOCKBACK_SECS: f32 = 0.2;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Deals damage to an entity with [`Health`].
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Damage {
+    pub entity: Entity,
+    pub amount: f32,
+    /// The velocity to knock the target back with, in pixels per second. It's
+    /// the same for every target, regardless of its mass.
+    pub knockback: Vec2,
+}
+
+/// Triggered on an entity when [`Damage`] actually hurts or kills it, which
+/// interrupts whatever it was doing. Damage that doesn't do anything, e.g.
+/// while the entity is [`Invulnerable`], doesn't count.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Hit {
+    pub entity: Entity,
+}
+
+/// Restores health to an entity with [`Health`], up to its maximum.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Heal {
+    pub entity: Entity,
+    pub amount: f32,
+}
+
+/// Triggered on an entity when its health runs out.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Died {
+    pub entity: Entity,
+}
+
+/// The actor can't take damage until the timer runs out.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Invulnerable(Timer);
+
+/// The actor is reeling from a hit.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Hurt(Timer);
+
+/// The actor's health ran out.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Dead;
+
+fn apply_damage(
+    damage: On<Damage>,
+    mut commands: Commands,
+    mut targets: Query<
+        (&mut Health, Option<&mut MovementController>),
+        (Without<Invulnerable>, Without<Dead>),
+    >,
+    mut velocities: Query<&mut LinearVelocity>,
+) {
+    let target = damage.entity;
+    let Ok((mut health, controller)) = targets.get_mut(target) else {
+        return;
+    };
+
+    health.current = (health.current - damage.amount).max(0.0);
+    commands.trigger(Hit { entity: target });
+
+    if damage.knockback != Vec2::ZERO
+        && let Ok(mut velocity) = velocities.get_mut(target)
+    {
+        velocity.0 = damage.knockback;
+        commands
+            .entity(target)
+            .insert(Knockback::from_seconds(KNOCKBACK_SECS));
+    }
+
+    if health.current > 0.0 {
+        commands.entity(target).insert((
+            Invulnerable(Timer::from_seconds(INVULNERABILITY_SECS, TimerMode::Once)),
+            Hurt(Timer::from_seconds(HURT_SECS, TimerMode::Once)),
+        ));
+        return;
+    }
+
+    if let Some(mut controller) = controller {
+        controller.intent = Vec2::ZERO;
+    }
+    commands.entity(target).remove::<Hurt>().insert(Dead);
+    commands.trigger(Died { entity: target });
+}
+
+fn apply_heal(heal: On<Heal>, mut targets: Query<&mut Health, Without<Dead>>) {
+    if let Ok(mut health) = targets.get_mut(heal.entity) {
+        health.current = (health.current + heal.amount).min(health.max);
+    }
+}
+
+fn tick_hurt_and_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut invulnerable: Query<(Entity, &mut Invulnerable)>,
+    mut hurt: Query<(Entity, &mut Hurt)>,
+) {
+    for (entity, mut invulnerable) in &mut invulnerable {
+        if invulnerable.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+    for (entity, mut hurt) in &mut hurt {
+        if hurt.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<Hurt>();
+        }
+    }
+}
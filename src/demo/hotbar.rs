@@ -0,0 +1,417 @@
+//! The hotbar: the first few slots of the player's [`Inventory`], shown at the
+//! bottom of the screen. One of them is selected at a time, and the item in
+//! it is what the player uses, e.g. a shovel or a bag of seeds.
+//!
+//! Select a slot with the number keys or the scroll wheel. Items entering or
+//! leaving the inventory are briefly listed above the hotbar.
+//!
+//! Also provides the [`item_slot`] widget that shows a single inventory slot,
+//! which the inventory menu uses as well.
+
+use bevy::{ecs::spawn::SpawnWith, input::mouse::AccumulatedMouseScroll, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::{LoadResource, load_pixel_art},
+    demo::{
+        inventory::{Inventory, ItemAssets, ItemCatalog, ItemStack, ItemsAdded, ItemsRemoved},
+        player::Player,
+    },
+    screens::Screen,
+    theme::palette::LABEL_TEXT,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Hotbar>()
+        .register_type::<ItemSlot>()
+        .register_type::<ItemNotice>()
+        .init_resource::<Hotbar>();
+    app.load_resource::<ItemSlotAssets>();
+    app.add_observer(notice_items_added);
+    app.add_observer(notice_items_removed);
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_hotbar);
+    app.add_systems(
+        Update,
+        (
+            select_hotbar_slot
+                .in_set(AppSystems::RecordInput)
+                .in_set(PausableSystems),
+            tick_item_notices.in_set(AppSystems::TickTimers),
+            (sync_item_slots, update_hotbar_selection)
+                .run_if(in_state(Screen::Gameplay))
+                .in_set(AppSystems::Update),
+        ),
+    );
+}
+
+/// How many inventory slots the hotbar shows.
+pub const HOTBAR_SIZE: usize = 8;
+
+const HOTBAR_KEYS: [KeyCode; HOTBAR_SIZE] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+];
+
+/// The size of an item slot on screen, in pixels.
+pub const SLOT_SIZE: f32 = 54.0;
+
+/// The size of an item icon inside its slot, in pixels.
+const ICON_SIZE: f32 = 36.0;
+
+/// The size of a corner of the selection box, in pixels.
+const SELECTION_CORNER_SIZE: f32 = 24.0;
+
+/// How long a line about items entering or leaving the inventory stays up,
+/// in seconds.
+const ITEM_NOTICE_SECS: f32 = 2.0;
+
+/// Which hotbar slot is selected.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct Hotbar {
+    pub selected: usize,
+}
+
+impl Hotbar {
+    /// The item stack in the selected slot of `inventory`, if any.
+    pub fn selected_stack<'a>(&self, inventory: &'a Inventory) -> Option<&'a ItemStack> {
+        inventory.get(self.selected)
+    }
+}
+
+/// Shows the contents of a slot of the player's [`Inventory`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct ItemSlot(pub usize);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ItemSlotIcon;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ItemSlotCount;
+
+/// The column above the hotbar that lists recent inventory changes.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ItemNotices;
+
+/// A line about items that entered or left the player's inventory, e.g.
+/// "+1 Milk".
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ItemNotice(Timer);
+
+/// The selection box around the selected hotbar slot.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HotbarSelection;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ItemSlotAssets {
+    #[dependency]
+    disc: Handle<Image>,
+    #[dependency]
+    selected_disc: Handle<Image>,
+    /// The corners of the selection box: top left, top right, bottom left and
+    /// bottom right.
+    #[dependency]
+    selection_corners: Vec<Handle<Image>>,
+}
+
+impl FromWorld for ItemSlotAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        let image = |path: String| -> Handle<Image> { load_pixel_art(assets, path) };
+        Self {
+            disc: image("UI/itemdisc_01.png".into()),
+            selected_disc: image("UI/itemdisc_02.png".into()),
+            selection_corners: ["tl", "tr", "bl", "br"]
+                .into_iter()
+                .map(|corner| image(format!("UI/selectbox_{corner}.png")))
+                .collect(),
+        }
+    }
+}
+
+/// A slot showing the icon and count of the item in `slot` of the player's
+/// inventory.
+pub fn item_slot(slot: usize, slot_assets: &ItemSlotAssets) -> impl Bundle {
+    (
+        Name::new("Item Slot"),
+        ItemSlot(slot),
+        Node {
+            width: px(SLOT_SIZE),
+            height: px(SLOT_SIZE),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        ImageNode::new(slot_assets.disc.clone()),
+        children![
+            (
+                Name::new("Item Slot Icon"),
+                ItemSlotIcon,
+                Node {
+                    width: px(ICON_SIZE),
+                    height: px(ICON_SIZE),
+                    ..default()
+                },
+                ImageNode::default(),
+                Visibility::Hidden,
+                // Let the slot itself receive picking events.
+                Pickable::IGNORE,
+            ),
+            (
+                Name::new("Item Slot Count"),
+                ItemSlotCount,
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: px(4),
+                    bottom: px(0),
+                    ..default()
+                },
+                Text::default(),
+                TextFont::from_font_size(16.0),
+                TextColor(LABEL_TEXT),
+                TextShadow::default(),
+                Pickable::IGNORE,
+            ),
+        ],
+    )
+}
+
+fn spawn_hotbar(mut commands: Commands, slot_assets: Res<ItemSlotAssets>) {
+    let slot_assets = slot_assets.clone();
+    commands.spawn((
+        Name::new("Item Notices"),
+        ItemNotices,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            bottom: px(SLOT_SIZE + 16.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+    commands.spawn((
+        Name::new("Hotbar"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            bottom: px(8),
+            justify_content: JustifyContent::Center,
+            column_gap: px(4),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for slot in 0..HOTBAR_SIZE {
+                parent
+                    .spawn(item_slot(slot, &slot_assets))
+                    .with_child(selection_box(&slot_assets));
+            }
+        })),
+    ));
+}
+
+fn selection_box(slot_assets: &ItemSlotAssets) -> impl Bundle {
+    let corner = |image: &Handle<Image>, left: bool, top: bool| {
+        (
+            Name::new("Hotbar Selection Corner"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: px(SELECTION_CORNER_SIZE),
+                height: px(SELECTION_CORNER_SIZE),
+                left: if left { px(-4) } else { Val::Auto },
+                right: if left { Val::Auto } else { px(-4) },
+                top: if top { px(-4) } else { Val::Auto },
+                bottom: if top { Val::Auto } else { px(-4) },
+                ..default()
+            },
+            ImageNode::new(image.clone()),
+        )
+    };
+    let [tl, tr, bl, br] = [0, 1, 2, 3].map(|index| &slot_assets.selection_corners[index]);
+    (
+        Name::new("Hotbar Selection"),
+        HotbarSelection,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        children![
+            corner(tl, true, true),
+            corner(tr, false, true),
+            corner(bl, true, false),
+            corner(br, false, false),
+        ],
+    )
+}
+
+fn select_hotbar_slot(
+    input: Res<ButtonInput<KeyCode>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut hotbar: ResMut<Hotbar>,
+) {
+    if let Some(slot) = HOTBAR_KEYS.iter().position(|&key| input.just_pressed(key)) {
+        hotbar.selected = slot;
+    }
+    // Scrolling down moves the selection to the right.
+    if scroll.delta.y < 0.0 {
+        hotbar.selected = (hotbar.selected + 1) % HOTBAR_SIZE;
+    } else if scroll.delta.y > 0.0 {
+        hotbar.selected = (hotbar.selected + HOTBAR_SIZE - 1) % HOTBAR_SIZE;
+    }
+}
+
+/// Shows what's in the player's inventory in every [`ItemSlot`].
+fn sync_item_slots(
+    player: Query<Ref<Inventory>, With<Player>>,
+    slots: Query<(Ref<ItemSlot>, &Children)>,
+    mut icons: Query<(&mut ImageNode, &mut Visibility), With<ItemSlotIcon>>,
+    mut counts: Query<&mut Text, With<ItemSlotCount>>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+    asset_server: Res<AssetServer>,
+) {
+    let (Ok(inventory), Some(catalog)) = (player.single(), catalogs.get(&item_assets.catalog))
+    else {
+        return;
+    };
+
+    for (slot, children) in &slots {
+        if !inventory.is_changed() && !slot.is_added() {
+            continue;
+        }
+        let stack = inventory.get(slot.0);
+        let def = stack.and_then(|stack| catalog.get(&stack.item));
+
+        for &child in children {
+            if let Ok((mut icon, mut visibility)) = icons.get_mut(child) {
+                match def {
+                    Some(def) => {
+                        icon.image = def.icon(&asset_server);
+                        *visibility = Visibility::Inherited;
+                    }
+                    None => *visibility = Visibility::Hidden,
+                }
+            }
+            if let Ok(mut count) = counts.get_mut(child) {
+                count.0 = match stack {
+                    Some(stack) if stack.count > 1 => stack.count.to_string(),
+                    _ => String::new(),
+                };
+            }
+        }
+    }
+}
+
+fn update_hotbar_selection(
+    hotbar: Res<Hotbar>,
+    slot_assets: If<Res<ItemSlotAssets>>,
+    mut slots: Query<(&ItemSlot, &mut ImageNode, &Children)>,
+    mut selections: Query<&mut Visibility, With<HotbarSelection>>,
+) {
+    for (slot, mut disc, children) in &mut slots {
+        let mut selections = selections.iter_many_mut(children);
+        let Some(mut visibility) = selections.fetch_next() else {
+            continue;
+        };
+        let selected = slot.0 == hotbar.selected;
+        visibility.set_if_neq(if selected {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        let image = if selected {
+            &slot_assets.selected_disc
+        } else {
+            &slot_assets.disc
+        };
+        if disc.image != *image {
+            disc.image = image.clone();
+        }
+    }
+}
+
+fn notice_items_added(
+    added: On<ItemsAdded>,
+    mut commands: Commands,
+    players: Query<(), With<Player>>,
+    notices: Single<Entity, With<ItemNotices>>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+) {
+    if players.contains(added.entity) {
+        let name = item_name(&added.item, &item_assets, &catalogs);
+        commands
+            .entity(*notices)
+            .with_child(item_notice(format!("+{} {name}", added.count)));
+    }
+}
+
+fn notice_items_removed(
+    removed: On<ItemsRemoved>,
+    mut commands: Commands,
+    players: Query<(), With<Player>>,
+    notices: Single<Entity, With<ItemNotices>>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+) {
+    if players.contains(removed.entity) {
+        let name = item_name(&removed.item, &item_assets, &catalogs);
+        commands
+            .entity(*notices)
+            .with_child(item_notice(format!("-{} {name}", removed.count)));
+    }
+}
+
+/// The display name of `item`, or its id if it isn't in the catalog.
+fn item_name(item: &str, item_assets: &ItemAssets, catalogs: &Assets<ItemCatalog>) -> String {
+    catalogs
+        .get(&item_assets.catalog)
+        .and_then(|catalog| catalog.get(item))
+        .map_or_else(|| item.to_owned(), |def| def.name.clone())
+}
+
+fn item_notice(text: String) -> impl Bundle {
+    (
+        Name::new("Item Notice"),
+        ItemNotice(Timer::from_seconds(ITEM_NOTICE_SECS, TimerMode::Once)),
+        Text(text),
+        TextFont::from_font_size(16.0),
+        TextColor(LABEL_TEXT),
+        TextShadow::default(),
+        Pickable::IGNORE,
+    )
+}
+
+fn tick_item_notices(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut notices: Query<(Entity, &mut ItemNotice)>,
+) {
+    for (entity, mut notice) in &mut notices {
+        if notice.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
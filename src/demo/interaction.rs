@@ -0,0 +1,207 @@
+//! Interacting with things in the world, such as doors, NPCs, signs and crops.
+//!
+//! Give an entity an [`Interactable`] and observe [`Interacted`] on it. When
+//! the player is in range and facing it, a prompt with its label floats above
+//! it, and pressing the interact key triggers [`Interacted`].
+
+use std::borrow::Cow;
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems, Pause,
+    demo::{
+        carry::Carrying,
+        health::Dead,
+        movement::MovementController,
+        player::{INTERACT_KEY, Player},
+    },
+    screens::Screen,
+    theme::palette::LABEL_TEXT,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Interactable>()
+        .register_type::<InteractionFocus>()
+        .init_resource::<InteractionFocus>();
+
+    app.add_systems(
+        Update,
+        (
+            interact
+                .run_if(input_just_pressed(INTERACT_KEY))
+                .in_set(AppSystems::RecordInput),
+            (update_focus, update_prompt)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+}
+
+/// How closely the player needs to face something to interact with it, as the
+/// cosine of the angle between where they're facing and where it is.
+const MIN_FACING_DOT: f32 = 0.5;
+
+/// Things closer than this can be interacted with regardless of facing, since
+/// the player is practically standing on them.
+const FACING_GRACE_DISTANCE: f32 = 4.0;
+
+/// How far above its target the prompt floats, in world units.
+const PROMPT_HEIGHT: f32 = 16.0;
+
+const PROMPT_WIDTH: f32 = 200.0;
+const PROMPT_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+/// Something the player can interact with.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Interactable {
+    /// Shown in the prompt, e.g. "Open" or "Talk".
+    pub label: Cow<'static, str>,
+    /// How close the player needs to be, in world units.
+    pub range: f32,
+}
+
+impl Interactable {
+    pub fn new(label: impl Into<Cow<'static, str>>, range: f32) -> Self {
+        Self {
+            label: label.into(),
+            range,
+        }
+    }
+}
+
+/// Triggered on an [`Interactable`] when the player interacts with it.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Interacted {
+    pub entity: Entity,
+    /// Who did the interacting.
+    pub interactor: Entity,
+}
+
+/// The [`Interactable`] the player would interact with right now, if any.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct InteractionFocus(pub Option<Entity>);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct InteractionPrompt;
+
+fn update_focus(
+    player: Query<
+        (&GlobalTransform, &MovementController, Option<&Carrying>),
+        (With<Player>, Without<Dead>),
+    >,
+    interactables: Query<(Entity, &Interactable, &GlobalTransform)>,
+    mut focus: ResMut<InteractionFocus>,
+) {
+    let Ok((player_transform, controller, carrying)) = player.single() else {
+        focus.set_if_neq(InteractionFocus(None));
+        return;
+    };
+
+    // With full hands, the only thing to do is to put down what's in them.
+    if let Some(carrying) = carrying {
+        let carried = Some(carrying.get()).filter(|&entity| interactables.contains(entity));
+        focus.set_if_neq(InteractionFocus(carried));
+        return;
+    }
+
+    let player_pos = player_transform.translation().truncate();
+    let facing = controller.facing.normalize_or_zero();
+    let nearest = interactables
+        .iter()
+        .filter_map(|(entity, interactable, transform)| {
+            let offset = transform.translation().truncate() - player_pos;
+            let distance = offset.length();
+            let in_range = distance <= interactable.range;
+            let faced = distance <= FACING_GRACE_DISTANCE
+                || facing.dot(offset / distance) >= MIN_FACING_DOT;
+            (in_range && faced).then_some((entity, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity);
+    focus.set_if_neq(InteractionFocus(nearest));
+}
+
+fn interact(
+    mut commands: Commands,
+    player: Single<Entity, (With<Player>, Without<Dead>)>,
+    focus: Res<InteractionFocus>,
+) {
+    if let Some(entity) = focus.0 {
+        commands.trigger(Interacted {
+            entity,
+            interactor: *player,
+        });
+    }
+}
+
+/// Float a prompt with the label of the focused [`Interactable`] above it.
+fn update_prompt(
+    mut commands: Commands,
+    focus: Res<InteractionFocus>,
+    interactables: Query<(&Interactable, &GlobalTransform)>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut prompt: Query<(Entity, &mut Node, &Children), With<InteractionPrompt>>,
+    mut texts: Query<&mut Text>,
+) {
+    let (camera, camera_transform) = *camera;
+    let target = focus.0.and_then(|entity| interactables.get(entity).ok());
+    let position = target.and_then(|(_, transform)| {
+        let above = transform.translation() + Vec3::Y * PROMPT_HEIGHT;
+        camera.world_to_viewport(camera_transform, above).ok()
+    });
+    let (Some((interactable, _)), Some(position)) = (target, position) else {
+        for (entity, ..) in &prompt {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let text = format!("E  {}", interactable.label);
+    let left = px(position.x - PROMPT_WIDTH / 2.0);
+    let top = px(position.y);
+
+    let Ok((_, mut node, children)) = prompt.single_mut() else {
+        commands.spawn((
+            Name::new("Interaction Prompt"),
+            InteractionPrompt,
+            Node {
+                position_type: PositionType::Absolute,
+                left,
+                top,
+                width: px(PROMPT_WIDTH),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+            DespawnOnExit(Pause(false)),
+            children![(
+                Name::new("Interaction Prompt Text"),
+                Text(text),
+                TextFont::from_font_size(16.0),
+                TextColor(LABEL_TEXT),
+                Node {
+                    padding: UiRect::axes(px(6), px(2)),
+                    ..default()
+                },
+                BackgroundColor(PROMPT_BACKGROUND),
+            )],
+        ));
+        return;
+    };
+
+    node.left = left;
+    node.top = top;
+    for &child in children {
+        if let Ok(mut label) = texts.get_mut(child)
+            && label.0 != text
+        {
+            label.0 = text.clone();
+        }
+    }
+}
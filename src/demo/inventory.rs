@@ -0,0 +1,306 @@
+//! Items and inventories.
+//!
+//! Every item is defined in `data/items.items.ron`, keyed by its id. An
+//! [`Inventory`] holds stacks of items in a fixed number of slots.
+//!
+//! Observe [`InventoryChanged`] to hear about any change to an inventory,
+//! including items being moved around, or [`ItemsAdded`] and [`ItemsRemoved`]
+//! to hear about items coming and going.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems,
+    asset_tracking::{LoadResource, load_pixel_art},
+    data::LoadRonAsset,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Inventory>()
+        .register_type::<ItemStack>()
+        .register_type::<InventoryTotals>();
+    app.init_ron_asset::<ItemCatalog>("items.ron");
+    app.load_resource::<ItemAssets>();
+
+    app.add_systems(Update, report_inventory_changes.in_set(AppSystems::Update));
+}
+
+/// What kind of thing an item is.
+#[derive(Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemCategory {
+    Tool,
+    Seed,
+    Crop,
+    Produce,
+    Fish,
+    Material,
+}
+
+/// The definition of a single item.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ItemDef {
+    pub name: String,
+    /// The path of the image shown in inventory slots.
+    pub icon: String,
+    /// How many of the item fit in a single slot.
+    pub stack_size: u32,
+    pub category: ItemCategory,
+}
+
+impl ItemDef {
+    pub fn icon(&self, asset_server: &AssetServer) -> Handle<Image> {
+        load_pixel_art(asset_server, &self.icon)
+    }
+}
+
+/// All items in the game.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct ItemCatalog {
+    /// Keyed by item id, e.g. `carrot_seeds`.
+    items: HashMap<String, ItemDef>,
+}
+
+impl ItemCatalog {
+    pub fn get(&self, item: &str) -> Option<&ItemDef> {
+        self.items.get(item)
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ItemAssets {
+    #[dependency]
+    pub catalog: Handle<ItemCatalog>,
+}
+
+impl FromWorld for ItemAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            catalog: assets.load("data/items.items.ron"),
+        }
+    }
+}
+
+/// A number of the same item in a single slot.
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, PartialEq, Eq)]
+pub struct ItemStack {
+    /// The item id.
+    pub item: String,
+    pub count: u32,
+}
+
+/// Stacks of items, in a fixed number of slots.
+#[derive(Component, Serialize, Deserialize, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(InventoryTotals)]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+}
+
+impl Inventory {
+    /// An empty inventory with `size` slots.
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![None; size],
+        }
+    }
+
+    /// Fills the first slots with `stacks`, e.g. for a starting inventory.
+    /// Stack sizes aren't checked.
+    pub fn with_stacks(mut self, stacks: impl IntoIterator<Item = ItemStack>) -> Self {
+        for (slot, stack) in self.slots.iter_mut().zip(stacks) {
+            *slot = Some(stack);
+        }
+        self
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&ItemStack> {
+        self.slots.get(slot).and_then(Option::as_ref)
+    }
+
+    /// How many of `item` there are across all slots.
+    pub fn count(&self, item: &str) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// Adds `count` of `item`, topping up existing stacks before filling
+    /// empty slots. Returns how many didn't fit.
+    pub fn add(&mut self, catalog: &ItemCatalog, item: &str, count: u32) -> u32 {
+        let Some(def) = catalog.get(item) else {
+            warn!("Tried to add unknown item {item:?} to an inventory");
+            return count;
+        };
+
+        let mut left = count;
+        for stack in self.slots.iter_mut().flatten() {
+            if left == 0 {
+                break;
+            }
+            if stack.item == item && stack.count < def.stack_size {
+                let moved = left.min(def.stack_size - stack.count);
+                stack.count += moved;
+                left -= moved;
+            }
+        }
+        for slot in &mut self.slots {
+            if left == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let moved = left.min(def.stack_size);
+                *slot = Some(ItemStack {
+                    item: item.to_owned(),
+                    count: moved,
+                });
+                left -= moved;
+            }
+        }
+        left
+    }
+
+    /// Removes `count` of `item`, taking from the last stacks first. Removes
+    /// nothing and returns `false` if there aren't enough.
+    pub fn remove(&mut self, item: &str, count: u32) -> bool {
+        if self.count(item) < count {
+            return false;
+        }
+
+        let mut left = count;
+        for slot in self.slots.iter_mut().rev() {
+            if left == 0 {
+                break;
+            }
+            let Some(stack) = slot.as_mut().filter(|stack| stack.item == item) else {
+                continue;
+            };
+            let moved = left.min(stack.count);
+            stack.count -= moved;
+            left -= moved;
+            if stack.count == 0 {
+                *slot = None;
+            }
+        }
+        true
+    }
+
+    /// Moves the stack in `from` onto `to`. Stacks of the same item are
+    /// merged as far as they fit, and anything else swaps places.
+    pub fn stack(&mut self, catalog: &ItemCatalog, from: usize, to: usize) {
+        if from == to || from >= self.slots.len() || to >= self.slots.len() {
+            return;
+        }
+
+        if let (Some(source), Some(target)) = (&self.slots[from], &self.slots[to])
+            && source.item == target.item
+        {
+            let stack_size = catalog.get(&source.item).map_or(1, |def| def.stack_size);
+            let moved = source.count.min(stack_size.saturating_sub(target.count));
+            if moved > 0 {
+                self.slots[to].as_mut().unwrap().count += moved;
+                let source = self.slots[from].as_mut().unwrap();
+                source.count -= moved;
+                if source.count == 0 {
+                    self.slots[from] = None;
+                }
+                return;
+            }
+        }
+        self.slots.swap(from, to);
+    }
+
+    /// Moves half of the stack in `slot` into the first empty slot. Returns
+    /// the slot the new stack ended up in.
+    pub fn split(&mut self, slot: usize) -> Option<usize> {
+        let half = self.get(slot)?.count / 2;
+        if half == 0 {
+            return None;
+        }
+        let empty = self.slots.iter().position(Option::is_none)?;
+        let stack = self.slots[slot].as_mut()?;
+        stack.count -= half;
+        self.slots[empty] = Some(ItemStack {
+            item: stack.item.clone(),
+            count: half,
+        });
+        Some(empty)
+    }
+
+    fn totals(&self) -> HashMap<String, u32> {
+        let mut totals = HashMap::new();
+        for stack in self.slots.iter().flatten() {
+            *totals.entry(stack.item.clone()).or_default() += stack.count;
+        }
+        totals
+    }
+}
+
+/// Triggered on an entity whenever its [`Inventory`] changes in any way.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct InventoryChanged {
+    pub entity: Entity,
+}
+
+/// Triggered on an entity when items are added to its [`Inventory`].
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ItemsAdded {
+    pub entity: Entity,
+    pub item: String,
+    pub count: u32,
+}
+
+/// Triggered on an entity when items are removed from its [`Inventory`].
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ItemsRemoved {
+    pub entity: Entity,
+    pub item: String,
+    pub count: u32,
+}
+
+/// How many of each item an [`Inventory`] held when its changes were last
+/// reported.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+struct InventoryTotals(HashMap<String, u32>);
+
+fn report_inventory_changes(
+    mut commands: Commands,
+    mut inventories: Query<(Entity, Ref<Inventory>, &mut InventoryTotals), Changed<Inventory>>,
+) {
+    for (entity, inventory, mut totals) in &mut inventories {
+        let new_totals = inventory.totals();
+        // What an inventory starts out with wasn't added to it.
+        if !inventory.is_added() {
+            for (item, &count) in &new_totals {
+                let old = totals.0.get(item).copied().unwrap_or_default();
+                if count > old {
+                    commands.trigger(ItemsAdded {
+                        entity,
+                        item: item.clone(),
+                        count: count - old,
+                    });
+                }
+            }
+            for (item, &old) in &totals.0 {
+                let count = new_totals.get(item).copied().unwrap_or_default();
+                if count < old {
+                    commands.trigger(ItemsRemoved {
+                        entity,
+                        item: item.clone(),
+                        count: old - count,
+                    });
+                }
+            }
+        }
+        totals.0 = new_totals;
+        commands.trigger(InventoryChanged { entity });
+    }
+}
@@ -6,16 +6,22 @@ use bevy_ecs_tiled::prelude::*;
 
 use crate::{
     asset_tracking::LoadResource,
-    audio::music,
-    demo::player::{Player, PlayerAssets, player},
+    audio::music::MusicDirector,
+    demo::{
+        player::{Player, PlayerAssets, player},
+        save::LoadedSave,
+        tiles::MapTiles,
+    },
     screens::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.load_resource::<LevelAssets>()
+        .init_resource::<PlayerSpawnPoint>()
         .register_type::<SpawnTile>()
         .register_type::<TeleportTile>()
         .register_type::<TileId>()
+        .register_type::<MapMusic>()
         .add_systems(
             Update,
             (
@@ -23,10 +29,15 @@ pub(super) fn plugin(app: &mut App) {
                 handle_tile_id_added,
                 set_player_spawn_from_tile,
                 player_teleport,
+                play_map_music.run_if(in_state(Screen::Gameplay)),
             ),
-        );
+        )
+        .add_systems(OnExit(Screen::Gameplay), stop_level_music);
 }
 
+/// The size of a single map tile in world units.
+pub const TILE_SIZE: f32 = 16.0;
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct LevelAssets {
@@ -50,6 +61,15 @@ impl FromWorld for LevelAssets {
 #[reflect(Component, Default)]
 struct SpawnTile;
 
+/// The music that plays while the player is on a map. Set this as a property
+/// on a map in Tiled, otherwise the level's default music plays.
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct MapMusic {
+    /// The asset path of the music track.
+    track: String,
+}
+
 #[derive(Component, Default, Debug, Reflect)]
 #[reflect(Component, Default)]
 struct TeleportTile {
@@ -62,6 +82,12 @@ struct TileId {
     id: String,
 }
 
+/// Where the player respawns after dying: the last [`SpawnTile`] they were
+/// placed at.
+#[derive(Resource, Default, Debug, Reflect)]
+#[reflect(Resource, Default)]
+pub struct PlayerSpawnPoint(pub Vec3);
+
 #[derive(Resource, Default, Debug, Reflect)]
 #[reflect(Resource, Default)]
 struct TileLocationLookup {
@@ -72,7 +98,6 @@ struct TileLocationLookup {
 pub fn spawn_level(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    level_assets: Res<LevelAssets>,
     player_assets: Res<PlayerAssets>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
@@ -82,13 +107,7 @@ pub fn spawn_level(
             TiledWorld(asset_server.load("Maps/one.world")),
             Visibility::default(),
             DespawnOnExit(Screen::Gameplay),
-            children![
-                player(100.0, &player_assets, &mut texture_atlas_layouts),
-                (
-                    Name::new("Gameplay Music"),
-                    music(level_assets.music.clone())
-                )
-            ],
+            children![player(100.0, &player_assets, &mut texture_atlas_layouts)],
         ))
         .observe(
             |collider_created: On<TiledEvent<ColliderCreated>>, mut commands: Commands| {
@@ -99,16 +118,46 @@ pub fn spawn_level(
         );
 }
 
+/// Crossfades to the music of the map the player is on.
+fn play_map_music(
+    player: Single<&GlobalTransform, With<Player>>,
+    map_tiles: MapTiles,
+    map_music: Query<&MapMusic>,
+    level_assets: Res<LevelAssets>,
+    asset_server: Res<AssetServer>,
+    mut director: ResMut<MusicDirector>,
+) {
+    let track = map_tiles
+        .map_at(player.translation().truncate())
+        .and_then(|map| map_music.get(map).ok())
+        .map_or_else(
+            || level_assets.music.clone(),
+            |music| asset_server.load(&music.track),
+        );
+    if director.current() != Some(&track) {
+        director.play(track);
+    }
+}
+
+fn stop_level_music(mut director: ResMut<MusicDirector>) {
+    director.stop();
+}
+
 fn set_player_spawn_from_tile(
     mut player_query: Query<&mut Transform, With<Player>>,
     spawn_tile: Query<&GlobalTransform, Added<SpawnTile>>,
+    mut spawn_point: ResMut<PlayerSpawnPoint>,
+    loaded_save: Option<Res<LoadedSave>>,
 ) {
     for tile_transform in &spawn_tile {
-        let mut spawn_pos = tile_transform.translation();
+        let spawn_pos = tile_transform.translation() + Vec3::new(8.0, 8.0, 0.0);
+        spawn_point.0 = spawn_pos;
 
+        // A loaded game continues wherever the player was when it was saved.
+        if loaded_save.is_some() {
+            continue;
+        }
         for mut player_transform in &mut player_query {
-            spawn_pos.x += 8.0;
-            spawn_pos.y += 8.0;
             player_transform.translation = spawn_pos;
         }
     }
@@ -119,18 +168,16 @@ fn player_teleport(
     tiles: Query<(&TeleportTile, &GlobalTransform), Without<Player>>,
     lookup: If<Res<TileLocationLookup>>,
 ) {
-    let tile_size = 16.0;
-
     for mut player in &mut player_query {
         let player_pos = player.translation;
 
         for (teleport, tile_transform) in &tiles {
             // Get tile center
             let tile_center =
-                tile_transform.translation() + Vec3::new(tile_size * 0.5, -tile_size * 0.5, 0.0);
+                tile_transform.translation() + Vec3::new(TILE_SIZE * 0.5, -TILE_SIZE * 0.5, 0.0);
 
             // Check if player is inside this tile (simple AABB check)
-            let half_size = Vec2::new(tile_size * 0.5, tile_size * 0.5);
+            let half_size = Vec2::new(TILE_SIZE * 0.5, TILE_SIZE * 0.5);
             let delta = Vec2::new(
                 (player_pos.x - tile_center.x).abs(),
                 (player_pos.y - tile_center.y).abs(),
@@ -5,16 +5,58 @@
 
 use bevy::prelude::*;
 
+mod ambience;
+mod animals;
 mod animation;
+pub mod behavior;
+pub mod carry;
+pub mod clock;
+pub mod combat;
+pub mod dialogue;
+pub mod emote;
+pub mod farming;
+pub mod fishing;
+pub mod footsteps;
+pub mod health;
+pub mod hotbar;
+pub mod interaction;
+pub mod inventory;
 pub mod level;
 mod movement;
+pub mod navigation;
+pub mod npc;
 pub mod player;
+pub mod save;
+pub mod tiles;
 
 pub(super) fn plugin(app: &mut App) {
+    // Plugin tuples only go up to 15 elements, so these are split in two.
     app.add_plugins((
-        animation::plugin,
-        level::plugin,
-        movement::plugin,
-        player::plugin,
+        (
+            ambience::plugin,
+            animals::plugin,
+            animation::plugin,
+            behavior::plugin,
+            carry::plugin,
+            clock::plugin,
+            combat::plugin,
+            dialogue::plugin,
+            emote::plugin,
+            farming::plugin,
+            fishing::plugin,
+            footsteps::plugin,
+        ),
+        (
+            health::plugin,
+            hotbar::plugin,
+            interaction::plugin,
+            inventory::plugin,
+            level::plugin,
+            movement::plugin,
+            navigation::plugin,
+            npc::plugin,
+            player::plugin,
+            save::plugin,
+        ),
     ));
 }
@@ -21,9 +21,12 @@ use crate::{AppSystems, PausableSystems};
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (apply_movement.before(IntegrationSystems::Velocity))
-            .chain()
-            .in_set(AppSystems::Update)
+        (
+            tick_knockback.in_set(AppSystems::TickTimers),
+            (apply_movement.before(IntegrationSystems::Velocity))
+                .chain()
+                .in_set(AppSystems::Update),
+        )
             .in_set(PausableSystems),
     );
 }
@@ -37,6 +40,10 @@ pub struct MovementController {
     /// The direction the character wants to move in.
     pub intent: Vec2,
 
+    /// The last direction the character moved in. Used to decide what the
+    /// character is facing while standing still.
+    pub facing: Vec2,
+
     /// Maximum speed in world units per second.
     /// 1 world unit = 1 pixel when using the default 2D camera and no physics engine.
     pub max_speed: f32,
@@ -46,14 +53,45 @@ impl Default for MovementController {
     fn default() -> Self {
         Self {
             intent: Vec2::ZERO,
+            facing: Vec2::X,
             // 400 pixels per second is a nice default, but we can still vary this per character.
             max_speed: 400.0,
         }
     }
 }
 
-fn apply_movement(mut query: Query<(&MovementController, &mut LinearVelocity)>) {
-    for (controller, mut linvel) in &mut query {
+/// While present, the character is being pushed around by physics (e.g. after
+/// taking a hit) and its [`MovementController`] intent is ignored.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Knockback(pub Timer);
+
+impl Knockback {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
+
+fn tick_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Knockback)>,
+) {
+    for (entity, mut knockback) in &mut query {
+        if knockback.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
+
+fn apply_movement(
+    mut query: Query<(&mut MovementController, &mut LinearVelocity), Without<Knockback>>,
+) {
+    for (mut controller, mut linvel) in &mut query {
+        if controller.intent != Vec2::ZERO {
+            controller.facing = controller.intent;
+        }
+
         // Directly overwrite the linear velocity
         linvel.0 = controller.intent * controller.max_speed;
     }
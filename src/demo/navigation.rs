@@ -0,0 +1,338 @@
+//! Grid pathfinding over the loaded Tiled maps.
+//!
+//! Every map gets a [`NavGrid`] with one cell per tile. Cells are blocked if a
+//! static collider (e.g. from the "collisions" object layer) overlaps them, or
+//! if any of their tiles is [`SolidTile`] or [`WaterTile`]. Grids are rebaked
+//! shortly after maps or colliders are spawned.
+//!
+//! On top of that, entities with a [`NavObstacle`] block the cell they stand
+//! on until they move away. Use [`NavGrids::find_path`] to get a path between
+//! two points.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use avian2d::prelude::{Collider, ColliderDisabled, RigidBody, SpatialQuery, SpatialQueryFilter};
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use crate::{
+    AppSystems,
+    demo::{fishing::WaterTile, level::TILE_SIZE},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SolidTile>()
+        .register_type::<NavObstacle>()
+        .init_resource::<NavGrids>();
+
+    app.add_observer(
+        |_: On<TiledEvent<MapCreated>>, mut grids: ResMut<NavGrids>| {
+            grids.schedule_rebake();
+        },
+    );
+    app.add_observer(
+        |_: On<TiledEvent<ColliderCreated>>, mut grids: ResMut<NavGrids>| {
+            grids.schedule_rebake();
+        },
+    );
+
+    app.add_systems(
+        Update,
+        (
+            tick_rebake.in_set(AppSystems::TickTimers),
+            (bake_nav_grids, update_nav_obstacles)
+                .chain()
+                .in_set(AppSystems::Update),
+        ),
+    );
+}
+
+/// How long to wait after the last map or collider was spawned before
+/// rebaking, in seconds. This gives physics a chance to pick up the new
+/// colliders, and batches the many colliders of a single map into one bake.
+const REBAKE_DELAY_SECS: f32 = 0.2;
+
+/// The part of a cell that needs to be free of colliders for it to be
+/// walkable. Slightly smaller than a tile so colliders that only graze a cell
+/// don't block it.
+const CELL_PROBE_SIZE: f32 = TILE_SIZE * 0.5;
+
+/// Movement costs, scaled so that they can be compared as integers.
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+/// A tile that can't be walked on, even without a collider. Set this as a
+/// property on tiles in the tileset.
+#[derive(Component, Default, Debug, Reflect, PartialEq, Eq, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct SolidTile;
+
+/// An entity that blocks the navigation cell it stands on, e.g. a crate.
+#[derive(Component, Default, Debug, Reflect, PartialEq, Eq, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct NavObstacle;
+
+/// The navigation grids of all loaded maps.
+#[derive(Resource, Default)]
+pub struct NavGrids {
+    grids: HashMap<Entity, NavGrid>,
+    rebake: Option<Timer>,
+}
+
+impl NavGrids {
+    /// Finds a walkable path from `from` to `to`, as a list of waypoints in
+    /// world space that ends at `to`. Both points need to be on the same map.
+    ///
+    /// Returns `None` if either point is off the maps, `to` is blocked, or
+    /// there is no way through.
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        self.grids
+            .values()
+            .find_map(|grid| grid.find_path(from, to))
+    }
+
+    fn schedule_rebake(&mut self) {
+        self.rebake = Some(Timer::from_seconds(REBAKE_DELAY_SECS, TimerMode::Once));
+    }
+}
+
+/// The walkable cells of a single map.
+pub struct NavGrid {
+    /// From the map's tile layers, to convert between cells and world space.
+    transform: GlobalTransform,
+    map_size: TilemapSize,
+    grid_size: TilemapGridSize,
+    tile_size: TilemapTileSize,
+    map_type: TilemapType,
+    anchor: TilemapAnchor,
+    /// Cells blocked by the map itself, indexed by `y * width + x`.
+    blocked: Vec<bool>,
+    /// Cells blocked by a [`NavObstacle`].
+    occupied: HashSet<UVec2>,
+}
+
+impl NavGrid {
+    fn cell_at(&self, point: Vec2) -> Option<UVec2> {
+        let local_pos = self
+            .transform
+            .affine()
+            .inverse()
+            .transform_point3(point.extend(0.0))
+            .truncate();
+        let tile_pos = TilePos::from_world_pos(
+            &local_pos,
+            &self.map_size,
+            &self.grid_size,
+            &self.tile_size,
+            &self.map_type,
+            &self.anchor,
+        )?;
+        Some(UVec2::new(tile_pos.x, tile_pos.y))
+    }
+
+    fn center(&self, cell: UVec2) -> Vec2 {
+        let local_pos = TilePos::new(cell.x, cell.y).center_in_world(
+            &self.map_size,
+            &self.grid_size,
+            &self.tile_size,
+            &self.map_type,
+            &self.anchor,
+        );
+        self.transform
+            .transform_point(local_pos.extend(0.0))
+            .truncate()
+    }
+
+    fn index(&self, cell: UVec2) -> usize {
+        (cell.y * self.map_size.x + cell.x) as usize
+    }
+
+    fn is_walkable(&self, cell: UVec2) -> bool {
+        !self.blocked[self.index(cell)] && !self.occupied.contains(&cell)
+    }
+
+    /// A* over the grid, moving in eight directions without cutting corners.
+    fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let start = self.cell_at(from)?;
+        let goal = self.cell_at(to)?;
+        // Whatever stands on the goal is probably what we want to get to.
+        if self.blocked[self.index(goal)] {
+            return None;
+        }
+
+        let walkable = |cell: UVec2| cell == goal || self.is_walkable(cell);
+        let mut open = BinaryHeap::from([Reverse((heuristic(start, goal), start.x, start.y))]);
+        let mut came_from = HashMap::<UVec2, UVec2>::new();
+        let mut cost = HashMap::from([(start, 0)]);
+
+        while let Some(Reverse((_, x, y))) = open.pop() {
+            let cell = UVec2::new(x, y);
+            if cell == goal {
+                let mut path = vec![to];
+                let mut cell = came_from.get(&goal).copied();
+                while let Some(step) = cell.filter(|&step| step != start) {
+                    path.push(self.center(step));
+                    cell = came_from.get(&step).copied();
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for offset in [
+                IVec2::X,
+                IVec2::NEG_X,
+                IVec2::Y,
+                IVec2::NEG_Y,
+                IVec2::ONE,
+                IVec2::NEG_ONE,
+                IVec2::new(1, -1),
+                IVec2::new(-1, 1),
+            ] {
+                let Some(next) = self.neighbor(cell, offset) else {
+                    continue;
+                };
+                if !walkable(next) {
+                    continue;
+                }
+                let diagonal = offset.x != 0 && offset.y != 0;
+                if diagonal {
+                    // Don't squeeze diagonally past a blocked corner.
+                    let corners = [IVec2::new(offset.x, 0), IVec2::new(0, offset.y)];
+                    if corners.into_iter().any(|corner| {
+                        self.neighbor(cell, corner)
+                            .is_none_or(|corner| !walkable(corner))
+                    }) {
+                        continue;
+                    }
+                }
+
+                let step_cost = if diagonal {
+                    DIAGONAL_COST
+                } else {
+                    STRAIGHT_COST
+                };
+                let next_cost = cost[&cell] + step_cost;
+                if cost.get(&next).is_none_or(|&known| next_cost < known) {
+                    cost.insert(next, next_cost);
+                    came_from.insert(next, cell);
+                    open.push(Reverse((next_cost + heuristic(next, goal), next.x, next.y)));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn neighbor(&self, cell: UVec2, offset: IVec2) -> Option<UVec2> {
+        let next = cell.as_ivec2() + offset;
+        (next.x >= 0
+            && next.y >= 0
+            && (next.x as u32) < self.map_size.x
+            && (next.y as u32) < self.map_size.y)
+            .then(|| next.as_uvec2())
+    }
+}
+
+/// Octile distance, which never overestimates on an eight-way grid.
+fn heuristic(from: UVec2, to: UVec2) -> u32 {
+    let delta = from.as_ivec2() - to.as_ivec2();
+    let (dx, dy) = (delta.x.unsigned_abs(), delta.y.unsigned_abs());
+    STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+}
+
+fn tick_rebake(time: Res<Time>, mut grids: ResMut<NavGrids>) {
+    if let Some(timer) = &mut grids.rebake {
+        timer.tick(time.delta());
+    }
+}
+
+fn bake_nav_grids(
+    mut grids: ResMut<NavGrids>,
+    maps: Query<Entity, With<TiledMap>>,
+    children: Query<&Children>,
+    tilemaps: Query<(
+        &TileStorage,
+        &TilemapSize,
+        &TilemapGridSize,
+        &TilemapTileSize,
+        &TilemapType,
+        &TilemapAnchor,
+        &GlobalTransform,
+    )>,
+    solid_tiles: Query<(), Or<(With<SolidTile>, With<WaterTile>)>>,
+    bodies: Query<&RigidBody>,
+    spatial_query: SpatialQuery,
+) {
+    if !grids
+        .rebake
+        .as_ref()
+        .is_some_and(|timer| timer.is_finished())
+    {
+        return;
+    }
+    grids.rebake = None;
+    grids.grids.clear();
+
+    let probe = Collider::rectangle(CELL_PROBE_SIZE, CELL_PROBE_SIZE);
+    let filter = SpatialQueryFilter::default();
+
+    for map in &maps {
+        let layers = children
+            .iter_descendants(map)
+            .filter_map(|entity| tilemaps.get(entity).ok())
+            .collect::<Vec<_>>();
+        // All tile layers of a map share the same grid.
+        let Some(&(_, map_size, grid_size, tile_size, map_type, anchor, transform)) =
+            layers.first()
+        else {
+            continue;
+        };
+
+        let mut grid = NavGrid {
+            transform: *transform,
+            map_size: *map_size,
+            grid_size: *grid_size,
+            tile_size: *tile_size,
+            map_type: *map_type,
+            anchor: *anchor,
+            blocked: vec![false; (map_size.x * map_size.y) as usize],
+            occupied: HashSet::new(),
+        };
+
+        for y in 0..map_size.y {
+            for x in 0..map_size.x {
+                let cell = UVec2::new(x, y);
+                let solid_tile = layers.iter().any(|(storage, ..)| {
+                    storage
+                        .get(&TilePos::new(x, y))
+                        .is_some_and(|tile| solid_tiles.contains(tile))
+                });
+                let solid_collider = || {
+                    spatial_query
+                        .shape_intersections(&probe, grid.center(cell), 0.0, &filter)
+                        .into_iter()
+                        .any(|entity| matches!(bodies.get(entity), Ok(RigidBody::Static)))
+                };
+                let index = grid.index(cell);
+                grid.blocked[index] = solid_tile || solid_collider();
+            }
+        }
+
+        grids.grids.insert(map, grid);
+    }
+}
+
+fn update_nav_obstacles(
+    mut grids: ResMut<NavGrids>,
+    obstacles: Query<&GlobalTransform, (With<NavObstacle>, Without<ColliderDisabled>)>,
+) {
+    for grid in grids.grids.values_mut() {
+        grid.occupied = obstacles
+            .iter()
+            .filter_map(|transform| grid.cell_at(transform.translation().truncate()))
+            .collect();
+    }
+}
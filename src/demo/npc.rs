@@ -0,0 +1,235 @@
+//! Non-player characters, such as goblins and skeletons.
+//!
+//! Give a Tiled object an [`NpcKind`] property to turn it into an NPC of that
+//! kind. NPCs move through a [`MovementController`] just like the player, steered
+//! by their [`Behavior`], and animate with the clips listed in [`NpcAssets`].
+
+use std::collections::HashMap;
+
+use avian2d::prelude::{Collider, LinearVelocity, LockedAxes, RigidBody};
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    demo::{
+        animation::{
+            CharacterAnimation, CharacterAnimationClip, CharacterAnimationState, CharacterBody,
+            CharacterClips,
+        },
+        behavior::{Behavior, Temperament},
+        combat::{MeleeAttack, Team},
+        health::{Dead, Health},
+        movement::MovementController,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<NpcKind>().register_type::<Npc>();
+    app.load_resource::<NpcAssets>();
+
+    app.add_systems(
+        Update,
+        (spawn_npcs, despawn_dead_npcs.in_set(PausableSystems)).in_set(AppSystems::Update),
+    );
+}
+
+/// What kind of NPC a Tiled object should spawn as.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component, Default)]
+pub enum NpcKind {
+    #[default]
+    Goblin,
+    Skeleton,
+}
+
+impl NpcKind {
+    fn max_speed(self) -> f32 {
+        match self {
+            Self::Goblin => 60.0,
+            Self::Skeleton => 40.0,
+        }
+    }
+
+    fn max_health(self) -> f32 {
+        match self {
+            Self::Goblin => 30.0,
+            Self::Skeleton => 50.0,
+        }
+    }
+
+    fn melee_attack(self) -> MeleeAttack {
+        match self {
+            Self::Goblin => MeleeAttack {
+                damage: 10.0,
+                knockback: 80.0,
+                reach: 10.0,
+                impact_frame: 5,
+                cooldown_secs: 1.0,
+            },
+            Self::Skeleton => MeleeAttack {
+                damage: 15.0,
+                knockback: 60.0,
+                reach: 12.0,
+                impact_frame: 4,
+                cooldown_secs: 1.5,
+            },
+        }
+    }
+
+    fn behavior(self) -> Behavior {
+        match self {
+            Self::Goblin => Behavior {
+                temperament: Temperament::Hostile,
+                sight_radius: 80.0,
+                ..default()
+            },
+            Self::Skeleton => Behavior {
+                temperament: Temperament::Hostile,
+                sight_radius: 56.0,
+                leash_radius: 96.0,
+                ..default()
+            },
+        }
+    }
+}
+
+/// Marks an [`NpcKind`] object that has been turned into an NPC.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Npc;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct NpcAssets {
+    clips: HashMap<NpcKind, CharacterClips>,
+}
+
+impl FromWorld for NpcAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        // Some of these strips are trimmed, so the frame counts follow the
+        // image widths rather than the file names.
+        let clip = |path, frames| CharacterAnimationClip::single(assets, path, frames, 96, 64);
+        Self {
+            clips: HashMap::from_iter([
+                (
+                    NpcKind::Goblin,
+                    CharacterClips(HashMap::from_iter([
+                        (
+                            CharacterAnimationState::Idling,
+                            clip("Characters/Goblin/PNG/spr_idle_strip9.png", 8),
+                        ),
+                        (
+                            CharacterAnimationState::Walking,
+                            clip("Characters/Goblin/PNG/spr_walk_strip8.png", 8),
+                        ),
+                        (
+                            CharacterAnimationState::Attacking,
+                            clip("Characters/Goblin/PNG/spr_attack_strip10.png", 9).once(),
+                        ),
+                        (
+                            CharacterAnimationState::Hurt,
+                            clip("Characters/Goblin/PNG/spr_hurt_strip8.png", 8).once(),
+                        ),
+                        (
+                            CharacterAnimationState::Dying,
+                            clip("Characters/Goblin/PNG/spr_death_strip13.png", 9).once(),
+                        ),
+                    ])),
+                ),
+                (
+                    NpcKind::Skeleton,
+                    CharacterClips(HashMap::from_iter([
+                        (
+                            CharacterAnimationState::Idling,
+                            clip("Characters/Skeleton/PNG/skeleton_idle_strip6.png", 6),
+                        ),
+                        (
+                            CharacterAnimationState::Walking,
+                            clip("Characters/Skeleton/PNG/skeleton_walk_strip8.png", 8),
+                        ),
+                        (
+                            CharacterAnimationState::Attacking,
+                            clip("Characters/Skeleton/PNG/skeleton_attack_strip7.png", 7).once(),
+                        ),
+                        (
+                            CharacterAnimationState::Hurt,
+                            clip("Characters/Skeleton/PNG/skeleton_hurt_strip7.png", 7).once(),
+                        ),
+                        (
+                            CharacterAnimationState::Dying,
+                            clip("Characters/Skeleton/PNG/skeleton_death_strip10.png", 10).once(),
+                        ),
+                    ])),
+                ),
+            ]),
+        }
+    }
+}
+
+/// The components that turn an [`NpcKind`] object into an NPC.
+fn npc(
+    kind: NpcKind,
+    npc_assets: &NpcAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+) -> impl Bundle {
+    let clips = npc_assets.clips[&kind].clone();
+    let texture_atlas_layout = texture_atlas_layouts.add(clips.atlas_layout());
+    let idle_animation = &clips.0[&CharacterAnimationState::Idling];
+    let animation = CharacterAnimation::new(idle_animation);
+    (
+        Npc,
+        Visibility::default(),
+        children![(
+            Name::new("Npc Body"),
+            CharacterBody,
+            Sprite::from_atlas_image(
+                idle_animation.base_image.clone(),
+                TextureAtlas {
+                    layout: texture_atlas_layout,
+                    index: animation.frame,
+                },
+            ),
+        )],
+        RigidBody::Dynamic,
+        LinearVelocity::default(),
+        Collider::circle(4.0),
+        LockedAxes::ROTATION_LOCKED,
+        MovementController {
+            max_speed: kind.max_speed(),
+            ..default()
+        },
+        Health::new(kind.max_health()),
+        Team::Monsters,
+        kind.melee_attack(),
+        kind.behavior(),
+        clips,
+        animation,
+    )
+}
+
+fn spawn_npcs(
+    mut commands: Commands,
+    spawners: Query<(Entity, &NpcKind), Without<Npc>>,
+    npc_assets: If<Res<NpcAssets>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    for (entity, &kind) in &spawners {
+        commands
+            .entity(entity)
+            .insert(npc(kind, &npc_assets, &mut texture_atlas_layouts));
+    }
+}
+
+/// Remove NPCs once their death animation has played out.
+fn despawn_dead_npcs(
+    mut commands: Commands,
+    npcs: Query<(Entity, &CharacterAnimation), (With<Npc>, With<Dead>)>,
+) {
+    for (entity, animation) in &npcs {
+        if animation.state() == CharacterAnimationState::Dying && animation.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
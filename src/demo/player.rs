@@ -1,13 +1,21 @@
 //! Player-specific behavior.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use crate::{
     AppSystems, PausableSystems,
     asset_tracking::LoadResource,
+    audio::SPATIAL_EAR_GAP,
     demo::{
-        animation::{PlayerAnimation, PlayerAnimationClip, PlayerAnimationState},
-        movement::MovementController,
+        animation::{
+            CharacterAnimation, CharacterAnimationClip, CharacterAnimationState, CharacterBody,
+            CharacterClips, CharacterHair, CharacterTool,
+        },
+        combat::{Attacking, MeleeAttack, Team},
+        health::{Dead, Health, Hurt, Invulnerable},
+        inventory::{Inventory, ItemStack},
+        level::PlayerSpawnPoint,
+        movement::{Knockback, MovementController},
     },
 };
 use avian2d::prelude::{Collider, LinearVelocity, LockedAxes, RigidBody};
@@ -15,6 +23,7 @@ use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.load_resource::<PlayerAssets>();
+    app.add_observer(respawn_player);
 
     // Record directional input as movement controls.
     app.add_systems(
@@ -31,9 +40,7 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct PlayerAssets {
-    pub actions: HashMap<PlayerAnimationState, PlayerAnimationClip>,
-    #[dependency]
-    pub steps: Vec<Handle<AudioSource>>,
+    pub actions: HashMap<CharacterAnimationState, CharacterAnimationClip>,
 }
 
 impl FromWorld for PlayerAssets {
@@ -42,8 +49,8 @@ impl FromWorld for PlayerAssets {
         Self {
             actions: HashMap::from_iter([
                 (
-                    PlayerAnimationState::Idling,
-                    PlayerAnimationClip::new(
+                    CharacterAnimationState::Idling,
+                    CharacterAnimationClip::new(
                         &assets,
                         "Characters/Human/IDLE/base_idle_strip9.png",
                         "Characters/Human/IDLE/spikeyhair_idle_strip9.png",
@@ -53,8 +60,8 @@ impl FromWorld for PlayerAssets {
                     ),
                 ),
                 (
-                    PlayerAnimationState::Walking,
-                    PlayerAnimationClip::new(
+                    CharacterAnimationState::Walking,
+                    CharacterAnimationClip::new(
                         &assets,
                         "Characters/Human/RUN/base_run_strip8.png",
                         "Characters/Human/RUN/spikeyhair_run_strip8.png",
@@ -63,13 +70,159 @@ impl FromWorld for PlayerAssets {
                         64,
                     ),
                 ),
+                (
+                    // There's no dedicated strip for standing still while
+                    // carrying, so hold the first frame of the carry strip.
+                    CharacterAnimationState::CarryIdling,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/CARRY/base_carry_strip8.png",
+                        "Characters/Human/CARRY/spikeyhair_carry_strip8.png",
+                        1,
+                        96,
+                        64,
+                    ),
+                ),
+                (
+                    CharacterAnimationState::CarryWalking,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/CARRY/base_carry_strip8.png",
+                        "Characters/Human/CARRY/spikeyhair_carry_strip8.png",
+                        8,
+                        96,
+                        64,
+                    ),
+                ),
+                (
+                    CharacterAnimationState::Casting,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/CASTING/base_casting_strip15.png",
+                        "Characters/Human/CASTING/spikeyhair_casting_strip15.png",
+                        15,
+                        96,
+                        64,
+                    )
+                    .once(),
+                ),
+                (
+                    CharacterAnimationState::Waiting,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/WAITING/base_waiting_strip9.png",
+                        "Characters/Human/WAITING/spikeyhair_waiting_strip9.png",
+                        9,
+                        96,
+                        64,
+                    ),
+                ),
+                (
+                    CharacterAnimationState::Reeling,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/REELING/base_reeling_strip13.png",
+                        "Characters/Human/REELING/spikeyhair_reeling_strip13.png",
+                        13,
+                        96,
+                        64,
+                    ),
+                ),
+                (
+                    CharacterAnimationState::Caught,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/CAUGHT/base_caught_strip10.png",
+                        "Characters/Human/CAUGHT/spikeyhair_caught_strip10.png",
+                        10,
+                        96,
+                        64,
+                    )
+                    .once(),
+                ),
+                (
+                    CharacterAnimationState::Attacking,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/ATTACK/base_attack_strip10.png",
+                        "Characters/Human/ATTACK/spikeyhair_attack_strip10.png",
+                        10,
+                        96,
+                        64,
+                    )
+                    .with_tool(&assets, "Characters/Human/ATTACK/tools_attack_strip10.png")
+                    .once(),
+                ),
+                (
+                    CharacterAnimationState::Digging,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/DIG/base_dig_strip13.png",
+                        "Characters/Human/DIG/spikeyhair_dig_strip13.png",
+                        13,
+                        96,
+                        64,
+                    )
+                    .with_tool(&assets, "Characters/Human/DIG/tools_dig_strip13.png")
+                    .once(),
+                ),
+                (
+                    CharacterAnimationState::Watering,
+                    CharacterAnimationClip {
+                        // The watering strip is short, so let it linger.
+                        duration: Duration::from_millis(120),
+                        ..CharacterAnimationClip::new(
+                            &assets,
+                            "Characters/Human/WATERING/base_watering_strip5.png",
+                            "Characters/Human/WATERING/spikeyhair_watering_strip5.png",
+                            5,
+                            96,
+                            64,
+                        )
+                        .with_tool(
+                            &assets,
+                            "Characters/Human/WATERING/tools_watering_strip5.png",
+                        )
+                        .once()
+                    },
+                ),
+                (
+                    CharacterAnimationState::Doing,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/DOING/base_doing_strip8.png",
+                        "Characters/Human/DOING/spikeyhair_doing_strip8.png",
+                        8,
+                        96,
+                        64,
+                    )
+                    .once(),
+                ),
+                (
+                    CharacterAnimationState::Hurt,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/HURT/base_hurt_strip8.png",
+                        "Characters/Human/HURT/spikeyhair_hurt_strip8.png",
+                        8,
+                        96,
+                        64,
+                    )
+                    .once(),
+                ),
+                (
+                    CharacterAnimationState::Dying,
+                    CharacterAnimationClip::new(
+                        &assets,
+                        "Characters/Human/DEATH/base_death_strip13.png",
+                        "Characters/Human/DEATH/spikeyhair_death_strip13.png",
+                        13,
+                        96,
+                        64,
+                    )
+                    .once(),
+                ),
             ]),
-            steps: vec![
-                assets.load("audio/sound_effects/step1.ogg"),
-                assets.load("audio/sound_effects/step2.ogg"),
-                assets.load("audio/sound_effects/step3.ogg"),
-                assets.load("audio/sound_effects/step4.ogg"),
-            ],
         }
     }
 }
@@ -82,20 +235,20 @@ pub fn player(
 ) -> impl Bundle {
     // A texture atlas is a way to split a single image into a grid of related images.
     // You can learn more in this example: https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
-    let layout = TextureAtlasLayout::from_grid(UVec2::new(96, 64), 9, 1, None, None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+    let clips = CharacterClips(player_assets.actions.clone());
+    let texture_atlas_layout = texture_atlas_layouts.add(clips.atlas_layout());
     let idle_animation = player_assets
         .actions
-        .get(&PlayerAnimationState::Idling)
+        .get(&CharacterAnimationState::Idling)
         .unwrap();
-    let player_animation = PlayerAnimation::new(idle_animation);
+    let player_animation = CharacterAnimation::new(idle_animation);
     (
         Name::new("Player"),
         Player,
         children![
             (
                 Name::new("Player Body"),
-                PlayerBody,
+                CharacterBody,
                 Sprite::from_atlas_image(
                     idle_animation.base_image.clone(),
                     TextureAtlas {
@@ -110,15 +263,33 @@ pub fn player(
             ),
             (
                 Name::new("Player Hair"),
-                PlayerHair,
+                CharacterHair,
                 Sprite::from_atlas_image(
-                    idle_animation.hair_image.clone(),
+                    idle_animation.hair_image.clone().unwrap_or_default(),
                     TextureAtlas {
                         layout: texture_atlas_layout.clone(),
                         index: player_animation.frame,
                     },
                 )
             ),
+            (
+                Name::new("Player Tool"),
+                CharacterTool,
+                Sprite::from_atlas_image(
+                    Handle::default(),
+                    TextureAtlas {
+                        layout: texture_atlas_layout.clone(),
+                        index: player_animation.frame,
+                    },
+                ),
+                Visibility::Hidden,
+                Transform::from_xyz(0.0, 0.0, 0.5),
+            ),
+            (
+                Name::new("Player Ears"),
+                SpatialListener::new(SPATIAL_EAR_GAP),
+                Transform::default(),
+            ),
         ],
         RigidBody::Dynamic,
         LinearVelocity::default(),
@@ -132,25 +303,50 @@ pub fn player(
             max_speed,
             ..default()
         },
+        Health::new(PLAYER_MAX_HEALTH),
+        Team::Player,
+        Inventory::new(INVENTORY_SIZE).with_stacks(STARTING_ITEMS.map(|(item, count)| ItemStack {
+            item: item.to_owned(),
+            count,
+        })),
+        MeleeAttack {
+            damage: 10.0,
+            knockback: 60.0,
+            reach: 12.0,
+            impact_frame: 5,
+            cooldown_secs: 0.1,
+        },
+        clips,
         player_animation,
     )
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
-#[reflect(Component)]
-pub struct Player;
+const PLAYER_MAX_HEALTH: f32 = 100.0;
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
-#[reflect(Component)]
-pub struct PlayerBody;
+/// How many slots the player's inventory has.
+pub const INVENTORY_SIZE: usize = 24;
+
+/// What the player starts out with, from the first slot onwards.
+const STARTING_ITEMS: [(&str, u32); 4] = [
+    ("shovel", 1),
+    ("watering_can", 1),
+    ("carrot_seeds", 10),
+    ("wheat_seeds", 10),
+];
+
+/// The key used to interact with the world, e.g. to pick up or put down objects.
+pub const INTERACT_KEY: KeyCode = KeyCode::KeyE;
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
-pub struct PlayerHair;
+pub struct Player;
 
 fn record_player_directional_input(
     input: Res<ButtonInput<KeyCode>>,
-    mut controller_query: Query<&mut MovementController, With<Player>>,
+    mut controller_query: Query<
+        &mut MovementController,
+        (With<Player>, Without<Dead>, Without<Attacking>),
+    >,
 ) {
     // Collect directional input.
     let mut intent = Vec2::ZERO;
@@ -188,3 +384,30 @@ fn camera_follow(
         }
     }
 }
+
+/// Whether the player is dead and their death animation has played out.
+pub fn player_death_finished(
+    player_query: Query<&CharacterAnimation, (With<Player>, With<Dead>)>,
+) -> bool {
+    player_query.single().is_ok_and(|animation| {
+        animation.state() == CharacterAnimationState::Dying && animation.is_finished()
+    })
+}
+
+/// Brings a dead player back to life at the last spawn point.
+#[derive(Event, Debug)]
+pub struct RespawnPlayer;
+
+fn respawn_player(
+    _: On<RespawnPlayer>,
+    mut commands: Commands,
+    spawn_point: Res<PlayerSpawnPoint>,
+    player: Single<(Entity, &mut Transform, &mut Health), With<Player>>,
+) {
+    let (entity, mut transform, mut health) = player.into_inner();
+    transform.translation = spawn_point.0;
+    health.current = health.max;
+    commands
+        .entity(entity)
+        .remove::<(Dead, Hurt, Invulnerable, Knockback)>();
+}
@@ -0,0 +1,375 @@
+//! Saving and loading games.
+//!
+//! A save holds the player's position and inventory, the in-game time, the
+//! farm plots and the [`DialogueFlags`]. Each save slot is a RON file in
+//! `saves/` (see [`storage`](crate::storage)), starting with a version number
+//! so that saves from older versions of the game can be told apart.
+//!
+//! Trigger [`NewGame`] to start playing in an empty slot, [`SaveGame`] to save
+//! into the [`ActiveSaveSlot`], and [`LoadGame`] to continue playing a save.
+//! A loaded game puts the player back where they were, unless that's no
+//! longer on the map they were saved on, e.g. after the map was changed. Then
+//! they start at the spawn point instead.
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::ResourceHandles,
+    demo::{
+        clock::GameClock,
+        dialogue::DialogueFlags,
+        farming::{Crop, FarmPlot, FarmingAssets, spawn_saved_plot},
+        inventory::Inventory,
+        level::PlayerSpawnPoint,
+        player::Player,
+        tiles::MapTiles,
+    },
+    screens::Screen,
+    storage::{self, StorageDir},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ActiveSaveSlot>()
+        .register_type::<PlayTime>()
+        .register_type::<SavedPositionCheck>()
+        .init_resource::<ActiveSaveSlot>()
+        .init_resource::<PlayTime>();
+
+    app.add_observer(new_game);
+    app.add_observer(save_game);
+    app.add_observer(load_game);
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_play_time);
+    app.add_systems(
+        Update,
+        (
+            tick_play_time
+                .run_if(in_state(Screen::Gameplay))
+                .in_set(AppSystems::TickTimers)
+                .in_set(PausableSystems),
+            (restore_save, check_saved_position)
+                .run_if(resource_exists::<LoadedSave>)
+                .in_set(AppSystems::Update),
+        ),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), forget_loaded_save);
+}
+
+/// The version of the save format. Bump this whenever a change to it would
+/// break reading older saves.
+pub const SAVE_VERSION: u32 = 1;
+
+/// How many save slots there are.
+pub const SAVE_SLOTS: usize = 3;
+
+/// How long the maps get to load before the player's saved position is
+/// checked against them, in seconds.
+const MAP_LOAD_SECS: f32 = 1.0;
+
+fn slot_path(slot: usize) -> String {
+    format!("saves/slot_{slot}.ron")
+}
+
+/// The save slot the current game is saved into.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct ActiveSaveSlot(pub usize);
+
+/// How long the current game has been played for, in seconds. Doesn't count
+/// time spent paused.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct PlayTime(pub f32);
+
+/// Everything that's kept in a save slot.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaveData {
+    version: u32,
+    /// The file name of the map the player is on, e.g. `sample.tmx`.
+    pub map: Option<String>,
+    pub player_position: [f32; 3],
+    pub inventory: Inventory,
+    pub clock: GameClock,
+    pub plots: Vec<SavedPlot>,
+    pub flags: DialogueFlags,
+    /// How long the game had been played for, in seconds.
+    #[serde(default)]
+    pub play_secs: f32,
+    /// When the game was saved, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+/// The player was put back where the loaded save says. Once the timer runs
+/// out, that is checked against the map they were saved on.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SavedPositionCheck(Timer);
+
+/// Just enough of a save to find out what version it is.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+/// A farm plot and whatever is growing on it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedPlot {
+    pub position: [f32; 3],
+    pub plot: FarmPlot,
+    pub crop: Option<Crop>,
+}
+
+impl SaveData {
+    /// Reads the save in `slot`, or `None` if the slot is empty.
+    pub fn read(slot: usize) -> Result<Option<Self>, BevyError> {
+        let Some(contents) = storage::read(StorageDir::Data, &slot_path(slot))? else {
+            return Ok(None);
+        };
+        // Check the version before anything else, since saves of other
+        // versions may not have the same fields.
+        let header: SaveHeader = ron::from_str(&contents)?;
+        if header.version != SAVE_VERSION {
+            return Err(BevyError::from(format!(
+                "save version {} can't be read, expected version {SAVE_VERSION}",
+                header.version
+            )));
+        }
+        Ok(Some(ron::from_str(&contents)?))
+    }
+
+    fn write(&self, slot: usize) -> Result<(), BevyError> {
+        let contents = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+        storage::write(StorageDir::Data, &slot_path(slot), &contents)
+    }
+
+    /// Deletes the save in `slot`, if there is one.
+    pub fn delete(slot: usize) -> Result<(), BevyError> {
+        storage::remove(StorageDir::Data, &slot_path(slot))
+    }
+}
+
+/// The slot that was saved into most recently, if any.
+pub fn last_saved_slot() -> Option<usize> {
+    (0..SAVE_SLOTS)
+        .filter_map(|slot| Some((slot, SaveData::read(slot).ok()??.saved_at)))
+        .max_by_key(|&(_, saved_at)| saved_at)
+        .map(|(slot, _)| slot)
+}
+
+/// The current time, in seconds since the Unix epoch.
+fn unix_time() -> u64 {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    }
+    // `SystemTime` isn't available on the web.
+    #[cfg(target_family = "wasm")]
+    {
+        (js_sys::Date::now() / 1000.0) as u64
+    }
+}
+
+/// Starts a new game that saves into `slot`.
+#[derive(Event, Debug)]
+pub struct NewGame {
+    pub slot: usize,
+}
+
+/// Saves the current game into the [`ActiveSaveSlot`].
+#[derive(Event, Debug)]
+pub struct SaveGame;
+
+/// Continues playing the save in `slot`.
+#[derive(Event, Debug)]
+pub struct LoadGame {
+    pub slot: usize,
+}
+
+/// The save the current game was loaded from.
+#[derive(Resource, Debug)]
+pub struct LoadedSave(pub SaveData);
+
+fn new_game(
+    new_game: On<NewGame>,
+    mut commands: Commands,
+    resource_handles: Res<ResourceHandles>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    commands.insert_resource(ActiveSaveSlot(new_game.slot));
+    commands.remove_resource::<LoadedSave>();
+    enter_gameplay(&resource_handles, &mut next_screen);
+}
+
+fn save_game(
+    _: On<SaveGame>,
+    slot: Res<ActiveSaveSlot>,
+    play_time: Res<PlayTime>,
+    player: Single<(&Transform, &Inventory), With<Player>>,
+    plots: Query<(&Transform, &FarmPlot, Option<&Crop>)>,
+    clock: Res<GameClock>,
+    flags: Res<DialogueFlags>,
+    map_tiles: MapTiles,
+    maps: Query<&TiledMap>,
+    asset_server: Res<AssetServer>,
+) {
+    let (transform, inventory) = player.into_inner();
+
+    let save = SaveData {
+        version: SAVE_VERSION,
+        map: map_name_at(
+            transform.translation.truncate(),
+            &map_tiles,
+            &maps,
+            &asset_server,
+        ),
+        player_position: transform.translation.to_array(),
+        inventory: inventory.clone(),
+        clock: clock.clone(),
+        plots: plots
+            .iter()
+            .map(|(transform, plot, crop)| SavedPlot {
+                position: transform.translation.to_array(),
+                plot: plot.clone(),
+                crop: crop.cloned(),
+            })
+            .collect(),
+        flags: flags.clone(),
+        play_secs: play_time.0,
+        saved_at: unix_time(),
+    };
+    match save.write(slot.0) {
+        Ok(()) => info!("Saved the game to slot {}", slot.0),
+        Err(error) => error!("Failed to save the game to slot {}: {error}", slot.0),
+    }
+}
+
+/// The file name of the map at `world_pos`, e.g. `sample.tmx`.
+fn map_name_at(
+    world_pos: Vec2,
+    map_tiles: &MapTiles,
+    maps: &Query<&TiledMap>,
+    asset_server: &AssetServer,
+) -> Option<String> {
+    let map = maps.get(map_tiles.map_at(world_pos)?).ok()?;
+    let path = asset_server.get_path(map.0.id())?;
+    Some(path.path().file_name()?.to_string_lossy().into_owned())
+}
+
+fn load_game(
+    load: On<LoadGame>,
+    mut commands: Commands,
+    resource_handles: Res<ResourceHandles>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let save = match SaveData::read(load.slot) {
+        Ok(Some(save)) => save,
+        Ok(None) => {
+            warn!("There is no save in slot {}", load.slot);
+            return;
+        }
+        Err(error) => {
+            error!("Failed to load slot {}: {error}", load.slot);
+            return;
+        }
+    };
+
+    commands.insert_resource(ActiveSaveSlot(load.slot));
+    commands.insert_resource(LoadedSave(save));
+    enter_gameplay(&resource_handles, &mut next_screen);
+}
+
+fn enter_gameplay(resource_handles: &ResourceHandles, next_screen: &mut NextState<Screen>) {
+    if resource_handles.is_all_done() {
+        next_screen.set(Screen::Gameplay);
+    } else {
+        next_screen.set(Screen::Loading);
+    }
+}
+
+fn reset_play_time(mut play_time: ResMut<PlayTime>) {
+    play_time.0 = 0.0;
+}
+
+fn tick_play_time(time: Res<Time>, mut play_time: ResMut<PlayTime>) {
+    play_time.0 += time.delta_secs();
+}
+
+/// Puts everything from the loaded save back into the freshly spawned level.
+fn restore_save(
+    mut commands: Commands,
+    loaded_save: Res<LoadedSave>,
+    player: Single<(Entity, &mut Transform, &mut Inventory), Added<Player>>,
+    mut clock: ResMut<GameClock>,
+    mut flags: ResMut<DialogueFlags>,
+    mut play_time: ResMut<PlayTime>,
+    farming_assets: If<Res<FarmingAssets>>,
+) {
+    let save = &loaded_save.0;
+    let (entity, mut transform, mut inventory) = player.into_inner();
+    transform.translation = Vec3::from_array(save.player_position);
+    commands
+        .entity(entity)
+        .insert(SavedPositionCheck(Timer::from_seconds(
+            MAP_LOAD_SECS,
+            TimerMode::Once,
+        )));
+    *inventory = save.inventory.clone();
+    *clock = save.clock.clone();
+    *flags = save.flags.clone();
+    play_time.0 = save.play_secs;
+
+    for saved in &save.plots {
+        spawn_saved_plot(
+            &mut commands,
+            Vec3::from_array(saved.position),
+            saved.plot.clone(),
+            saved.crop.clone(),
+            &farming_assets,
+        );
+    }
+}
+
+/// Moves the player to the spawn point if their saved position isn't on the
+/// map they were saved on.
+fn check_saved_position(
+    mut commands: Commands,
+    time: Res<Time>,
+    loaded_save: Res<LoadedSave>,
+    player: Single<(Entity, &mut Transform, &mut SavedPositionCheck), With<Player>>,
+    spawn_point: Res<PlayerSpawnPoint>,
+    map_tiles: MapTiles,
+    maps: Query<&TiledMap>,
+    asset_server: Res<AssetServer>,
+) {
+    let (entity, mut transform, mut check) = player.into_inner();
+    if !check.0.tick(time.delta()).is_finished() {
+        return;
+    }
+    commands.entity(entity).remove::<SavedPositionCheck>();
+
+    let map = map_name_at(
+        transform.translation.truncate(),
+        &map_tiles,
+        &maps,
+        &asset_server,
+    );
+    if map.is_none() || map != loaded_save.0.map {
+        warn!(
+            "The saved position isn't on {:?} anymore, starting at the spawn point instead",
+            loaded_save.0.map
+        );
+        transform.translation = spawn_point.0;
+    }
+}
+
+fn forget_loaded_save(mut commands: Commands) {
+    commands.remove_resource::<LoadedSave>();
+}
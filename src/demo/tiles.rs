@@ -0,0 +1,82 @@
+//! Looking up the tiles of the loaded Tiled maps by world position.
+//!
+//! Tile properties set in Tiled (e.g. [`WaterTile`](super::fishing::WaterTile))
+//! end up as components on the individual tile entities, so this is how
+//! gameplay code finds out what is under a given point.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_ecs_tiled::prelude::*;
+
+/// Finds tile entities across all tile layers, and the maps they belong to.
+#[derive(SystemParam)]
+pub struct MapTiles<'w, 's> {
+    tilemaps: Query<
+        'w,
+        's,
+        (
+            &'static TileStorage,
+            &'static TilemapSize,
+            &'static TilemapGridSize,
+            &'static TilemapTileSize,
+            &'static TilemapType,
+            &'static TilemapAnchor,
+            &'static GlobalTransform,
+        ),
+    >,
+    ancestors: Query<'w, 's, &'static ChildOf>,
+    maps: Query<'w, 's, (), With<TiledMap>>,
+}
+
+impl MapTiles<'_, '_> {
+    /// Returns the tile entities at `world_pos`, at most one per tile layer,
+    /// from the topmost layer down.
+    pub fn tiles_at(&self, world_pos: Vec2) -> impl Iterator<Item = Entity> + use<> {
+        let mut tiles: Vec<(f32, Entity)> = self
+            .tilemaps
+            .iter()
+            .filter_map(
+                |(storage, map_size, grid_size, tile_size, map_type, anchor, transform)| {
+                    let local_pos = transform
+                        .affine()
+                        .inverse()
+                        .transform_point3(world_pos.extend(0.0))
+                        .truncate();
+                    let tile_pos = TilePos::from_world_pos(
+                        &local_pos, map_size, grid_size, tile_size, map_type, anchor,
+                    )?;
+                    Some((transform.translation().z, storage.get(&tile_pos)?))
+                },
+            )
+            .collect();
+        tiles.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        tiles.into_iter().map(|(_, tile)| tile)
+    }
+
+    /// Returns the center of the tile at `world_pos`, if it's on a map.
+    pub fn tile_center(&self, world_pos: Vec2) -> Option<Vec2> {
+        self.tilemaps.iter().find_map(
+            |(_, map_size, grid_size, tile_size, map_type, anchor, transform)| {
+                let local_pos = transform
+                    .affine()
+                    .inverse()
+                    .transform_point3(world_pos.extend(0.0))
+                    .truncate();
+                let tile_pos = TilePos::from_world_pos(
+                    &local_pos, map_size, grid_size, tile_size, map_type, anchor,
+                )?;
+                let center =
+                    tile_pos.center_in_world(map_size, grid_size, tile_size, map_type, anchor);
+                Some(transform.transform_point(center.extend(0.0)).truncate())
+            },
+        )
+    }
+
+    /// Returns the map entity with a tile at `world_pos`, if any.
+    pub fn map_at(&self, world_pos: Vec2) -> Option<Entity> {
+        self.tiles_at(world_pos).find_map(|tile| {
+            self.ancestors
+                .iter_ancestors(tile)
+                .find(|&ancestor| self.maps.contains(ancestor))
+        })
+    }
+}
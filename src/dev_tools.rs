@@ -4,7 +4,10 @@ use bevy::{
     dev_tools::states::log_transitions, input::common_conditions::input_just_pressed, prelude::*,
 };
 
-use crate::screens::Screen;
+use crate::{
+    demo::{health::Damage, player::Player},
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Log `Screen` state transitions.
@@ -15,10 +18,23 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         toggle_debug_ui.run_if(input_just_pressed(TOGGLE_KEY)),
     );
+
+    // Hurt the player to test health and the game over flow.
+    app.add_systems(Update, damage_player.run_if(input_just_pressed(DAMAGE_KEY)));
 }
 
 const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
 
+const DAMAGE_KEY: KeyCode = KeyCode::KeyH;
+
 fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
     options.toggle();
 }
+
+fn damage_player(mut commands: Commands, player: Single<Entity, With<Player>>) {
+    commands.trigger(Damage {
+        entity: *player,
+        amount: 25.0,
+        knockback: Vec2::ZERO,
+    });
+}
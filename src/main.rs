@@ -5,17 +5,22 @@
 
 mod asset_tracking;
 mod audio;
+mod data;
 mod demo;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod menus;
 mod screens;
+mod settings;
+mod storage;
 mod theme;
 
 use avian2d::PhysicsPlugins;
-use bevy::{asset::AssetMetaCheck, camera::ScalingMode, prelude::*};
+use bevy::{asset::AssetMetaCheck, audio::AudioPlugin, camera::ScalingMode, prelude::*};
 use bevy_ecs_tiled::prelude::*;
 
+use crate::settings::Settings;
+
 fn main() -> AppExit {
     App::new().add_plugins(AppPlugin).run()
 }
@@ -24,6 +29,11 @@ pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
+        // Read the settings before adding Bevy plugins, so that audio and the
+        // window start out with them.
+        let saved_settings = Settings::read();
+        let settings = saved_settings.as_ref().cloned().unwrap_or_default();
+
         // Add Bevy plugins.
         app.add_plugins(
             DefaultPlugins
@@ -39,13 +49,25 @@ impl Plugin for AppPlugin {
                     primary_window: Window {
                         title: "Eager Omega".to_string(),
                         fit_canvas_to_parent: true,
+                        mode: settings.window_mode(),
                         ..default()
                     }
                     .into(),
                     ..default()
+                })
+                .set(AudioPlugin {
+                    global_volume: settings.global_volume(),
+                    default_spatial_scale: audio::spatial_scale(),
                 }),
         );
 
+        // Logging is only set up now, so report a broken settings file here.
+        if let Err(error) = &saved_settings {
+            warn!("Failed to read the settings, using the defaults instead: {error}");
+        }
+        app.insert_resource(settings.category_volumes());
+        app.insert_resource(settings);
+
         // initialize tiled map plugin
 
         app.add_plugins((
@@ -68,6 +90,7 @@ impl Plugin for AppPlugin {
             dev_tools::plugin,
             menus::plugin,
             screens::plugin,
+            settings::plugin,
             theme::plugin,
         ));
 
@@ -2,7 +2,9 @@
 
 use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::prelude::*};
+use crate::{
+    asset_tracking::LoadResource, audio::music::MusicDirector, menus::Menu, theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
@@ -13,6 +15,7 @@ pub(super) fn plugin(app: &mut App) {
 
     app.load_resource::<CreditsAssets>();
     app.add_systems(OnEnter(Menu::Credits), start_credits_music);
+    app.add_systems(OnExit(Menu::Credits), stop_credits_music);
 }
 
 fn spawn_credits_menu(mut commands: Commands) {
@@ -42,6 +45,7 @@ fn assets() -> impl Bundle {
         ["Ducky sprite", "CC0 by Caz Creates Games"],
         ["Button SFX", "CC0 by Jaszunio15"],
         ["Music", "CC BY 3.0 by Kevin MacLeod"],
+        ["Ambience", "CC0, synthesized for this game"],
         [
             "Bevy logo",
             "All rights reserved by the Bevy Foundation, permission granted for splash screen use when unmodified",
@@ -101,10 +105,10 @@ impl FromWorld for CreditsAssets {
     }
 }
 
-fn start_credits_music(mut commands: Commands, credits_music: Res<CreditsAssets>) {
-    commands.spawn((
-        Name::new("Credits Music"),
-        DespawnOnExit(Menu::Credits),
-        music(credits_music.music.clone()),
-    ));
+fn start_credits_music(mut director: ResMut<MusicDirector>, credits_music: Res<CreditsAssets>) {
+    director.play(credits_music.music.clone());
+}
+
+fn stop_credits_music(mut director: ResMut<MusicDirector>) {
+    director.stop();
 }
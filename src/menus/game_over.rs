@@ -0,0 +1,31 @@
+//! The game over menu, shown when the player dies.
+
+use bevy::prelude::*;
+
+use crate::{demo::player::RespawnPlayer, menus::Menu, screens::Screen, theme::widget};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::GameOver), spawn_game_over_menu);
+}
+
+fn spawn_game_over_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Game Over Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::GameOver),
+        children![
+            widget::header("You died"),
+            widget::button("Respawn", respawn),
+            widget::button("Quit to title", quit_to_title),
+        ],
+    ));
+}
+
+fn respawn(_: On<Pointer<Click>>, mut commands: Commands, mut next_menu: ResMut<NextState<Menu>>) {
+    commands.trigger(RespawnPlayer);
+    next_menu.set(Menu::None);
+}
+
+fn quit_to_title(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
@@ -0,0 +1,169 @@
+//! The inventory menu, showing every slot of the player's inventory.
+//!
+//! Drag an item onto another slot to move it there, stacking it with the same
+//! item or swapping places with anything else. Right-click a stack to split
+//! it in half.
+
+use bevy::{
+    ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed,
+    picking::pointer::PointerButton, prelude::*,
+};
+
+use crate::{
+    demo::{
+        hotbar::{HOTBAR_SIZE, ItemSlot, ItemSlotAssets, SLOT_SIZE, item_slot},
+        inventory::{Inventory, ItemAssets, ItemCatalog},
+        player::{INVENTORY_SIZE, Player},
+    },
+    menus::Menu,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<DraggedItem>();
+
+    app.add_systems(OnEnter(Menu::Inventory), spawn_inventory_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(
+            in_state(Menu::Inventory)
+                .and(input_just_pressed(KeyCode::Escape).or(input_just_pressed(KeyCode::KeyI))),
+        ),
+    );
+}
+
+/// The size of the icon that follows the pointer while dragging, in pixels.
+const DRAGGED_ICON_SIZE: f32 = 36.0;
+
+/// The icon following the pointer while an item is dragged.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DraggedItem;
+
+fn spawn_inventory_menu(mut commands: Commands, slot_assets: Res<ItemSlotAssets>) {
+    let slot_assets = slot_assets.clone();
+    commands.spawn((
+        widget::ui_root("Inventory Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Inventory),
+        children![
+            widget::header("Inventory"),
+            (
+                Name::new("Inventory Slots"),
+                Node {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::px(HOTBAR_SIZE as u16, SLOT_SIZE),
+                    row_gap: px(4),
+                    column_gap: px(4),
+                    ..default()
+                },
+                Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                    for slot in 0..INVENTORY_SIZE {
+                        parent
+                            .spawn(item_slot(slot, &slot_assets))
+                            .observe(start_drag)
+                            .observe(drag)
+                            .observe(drop_item)
+                            .observe(end_drag)
+                            .observe(split_stack);
+                    }
+                })),
+            ),
+            widget::button("Close", close_menu),
+        ],
+    ));
+}
+
+fn start_drag(
+    drag_start: On<Pointer<DragStart>>,
+    mut commands: Commands,
+    slots: Query<(&ItemSlot, &Children)>,
+    icons: Query<(&ImageNode, &Visibility)>,
+) {
+    let Ok((_, children)) = slots.get(drag_start.event_target()) else {
+        return;
+    };
+    // Only slots with something in them can be dragged.
+    let Some(image) = icons
+        .iter_many(children)
+        .find(|(_, visibility)| **visibility != Visibility::Hidden)
+        .map(|(icon, _)| icon.image.clone())
+    else {
+        return;
+    };
+
+    let position = drag_start.pointer_location.position;
+    commands.spawn((
+        Name::new("Dragged Item"),
+        DraggedItem,
+        Node {
+            position_type: PositionType::Absolute,
+            left: px(position.x - DRAGGED_ICON_SIZE / 2.0),
+            top: px(position.y - DRAGGED_ICON_SIZE / 2.0),
+            width: px(DRAGGED_ICON_SIZE),
+            height: px(DRAGGED_ICON_SIZE),
+            ..default()
+        },
+        ImageNode::new(image),
+        GlobalZIndex(3),
+        // Let the slot under the pointer receive the drop.
+        Pickable::IGNORE,
+        DespawnOnExit(Menu::Inventory),
+    ));
+}
+
+fn drag(drag: On<Pointer<Drag>>, mut dragged: Query<&mut Node, With<DraggedItem>>) {
+    let position = drag.pointer_location.position;
+    for mut node in &mut dragged {
+        node.left = px(position.x - DRAGGED_ICON_SIZE / 2.0);
+        node.top = px(position.y - DRAGGED_ICON_SIZE / 2.0);
+    }
+}
+
+fn drop_item(
+    drop: On<Pointer<DragDrop>>,
+    slots: Query<&ItemSlot>,
+    mut inventory: Single<&mut Inventory, With<Player>>,
+    item_assets: If<Res<ItemAssets>>,
+    catalogs: Res<Assets<ItemCatalog>>,
+) {
+    let (Ok(from), Ok(to), Some(catalog)) = (
+        slots.get(drop.dropped),
+        slots.get(drop.event_target()),
+        catalogs.get(&item_assets.catalog),
+    ) else {
+        return;
+    };
+    inventory.stack(catalog, from.0, to.0);
+}
+
+fn end_drag(
+    _: On<Pointer<DragEnd>>,
+    mut commands: Commands,
+    dragged: Query<Entity, With<DraggedItem>>,
+) {
+    for entity in &dragged {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn split_stack(
+    click: On<Pointer<Click>>,
+    slots: Query<&ItemSlot>,
+    mut inventory: Single<&mut Inventory, With<Player>>,
+) {
+    if click.button != PointerButton::Secondary {
+        return;
+    }
+    if let Ok(slot) = slots.get(click.event_target()) {
+        inventory.split(slot.0);
+    }
+}
+
+fn close_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
@@ -1,44 +1,44 @@
 //! The main menu (seen on the title screen).
 
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    demo::save::{LoadGame, last_saved_slot},
+    menus::Menu,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
 fn spawn_main_menu(mut commands: Commands) {
+    let last_slot = last_saved_slot();
     commands.spawn((
         widget::ui_root("Main Menu"),
         GlobalZIndex(2),
         DespawnOnExit(Menu::Main),
-        #[cfg(not(target_family = "wasm"))]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-            widget::button("Exit", exit_app),
-        ],
-        #[cfg(target_family = "wasm")]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-        ],
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            // Jump straight back into the most recent save.
+            if let Some(slot) = last_slot {
+                parent.spawn(widget::button(
+                    "Continue",
+                    move |_: On<Pointer<Click>>, mut commands: Commands| {
+                        commands.trigger(LoadGame { slot });
+                    },
+                ));
+            }
+            parent.spawn(widget::button("Play", open_save_slots_menu));
+            parent.spawn(widget::button("Settings", open_settings_menu));
+            parent.spawn(widget::button("Credits", open_credits_menu));
+            #[cfg(not(target_family = "wasm"))]
+            parent.spawn(widget::button("Exit", exit_app));
+        })),
     ));
 }
 
-fn enter_loading_or_gameplay_screen(
-    _: On<Pointer<Click>>,
-    resource_handles: Res<ResourceHandles>,
-    mut next_screen: ResMut<NextState<Screen>>,
-) {
-    if resource_handles.is_all_done() {
-        next_screen.set(Screen::Gameplay);
-    } else {
-        next_screen.set(Screen::Loading);
-    }
+fn open_save_slots_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveSlots);
 }
 
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
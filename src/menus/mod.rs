@@ -1,8 +1,11 @@
 //! The game's menus and transitions between them.
 
 mod credits;
+mod game_over;
+mod inventory;
 mod main;
 mod pause;
+mod save_slots;
 mod settings;
 
 use bevy::prelude::*;
@@ -12,7 +15,10 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_plugins((
         credits::plugin,
+        game_over::plugin,
+        inventory::plugin,
         main::plugin,
+        save_slots::plugin,
         settings::plugin,
         pause::plugin,
     ));
@@ -26,4 +32,8 @@ pub enum Menu {
     Credits,
     Settings,
     Pause,
+    GameOver,
+    Inventory,
+    SaveSlots,
+    DeleteSave,
 }
@@ -2,7 +2,7 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{demo::save::SaveGame, menus::Menu, screens::Screen, theme::widget};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
@@ -20,12 +20,17 @@ fn spawn_pause_menu(mut commands: Commands) {
         children![
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
+            widget::button("Save", save_game),
             widget::button("Settings", open_settings_menu),
             widget::button("Quit to title", quit_to_title),
         ],
     ));
 }
 
+fn save_game(_: On<Pointer<Click>>, mut commands: Commands) {
+    commands.trigger(SaveGame);
+}
+
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
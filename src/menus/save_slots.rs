@@ -0,0 +1,231 @@
+//! The save slot menu, where a new game is started in an empty slot or an
+//! existing save is continued or deleted.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    demo::save::{LoadGame, NewGame, SAVE_SLOTS, SaveData},
+    menus::Menu,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SlotToDelete>()
+        .init_resource::<SlotToDelete>();
+
+    app.add_systems(OnEnter(Menu::SaveSlots), spawn_save_slots_menu);
+    app.add_systems(OnEnter(Menu::DeleteSave), spawn_delete_save_menu);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(in_state(Menu::SaveSlots).and(input_just_pressed(KeyCode::Escape))),
+            cancel_delete
+                .run_if(in_state(Menu::DeleteSave).and(input_just_pressed(KeyCode::Escape))),
+        ),
+    );
+}
+
+/// The slot the delete confirmation is asking about.
+#[derive(Resource, Default, Debug, Reflect)]
+#[reflect(Resource, Default)]
+struct SlotToDelete(usize);
+
+/// What's in a save slot.
+enum SlotState {
+    Empty,
+    Saved,
+    /// There is a save, but it's corrupt or from another version of the game.
+    Unreadable,
+}
+
+fn spawn_save_slots_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Save Slots Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::SaveSlots),
+        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+            parent.spawn(widget::header("Choose a save"));
+            for slot in 0..SAVE_SLOTS {
+                parent.spawn(save_slot(slot));
+            }
+            parent.spawn(widget::button("Back", go_back_on_click));
+        })),
+    ));
+}
+
+/// A row describing the save in `slot`, with buttons for what can be done
+/// with it.
+fn save_slot(slot: usize) -> impl Bundle {
+    let title = format!("Slot {}", slot + 1);
+    let (state, summary, details) = match SaveData::read(slot) {
+        Ok(Some(save)) => (
+            SlotState::Saved,
+            format!(
+                "{title} - {} - {:?} {}",
+                location_name(save.map.as_deref()),
+                save.clock.season(),
+                save.clock.day_of_season()
+            ),
+            format!(
+                "Played {} - Saved {}",
+                format_play_time(save.play_secs),
+                format_timestamp(save.saved_at)
+            ),
+        ),
+        Ok(None) => (SlotState::Empty, title, "Empty".to_owned()),
+        Err(error) => {
+            warn!("Failed to read save slot {slot}: {error}");
+            (
+                SlotState::Unreadable,
+                title,
+                "This save can't be read".to_owned(),
+            )
+        }
+    };
+
+    (
+        Name::new("Save Slot"),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: px(20),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Save Slot Info"),
+                Node {
+                    width: px(600),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: px(4),
+                    ..default()
+                },
+                children![widget::label(summary), widget::label(details)],
+            ));
+            match state {
+                SlotState::Empty => {
+                    parent.spawn(widget::button_medium(
+                        "New Game",
+                        move |_: On<Pointer<Click>>, mut commands: Commands| {
+                            commands.trigger(NewGame { slot });
+                        },
+                    ));
+                }
+                SlotState::Saved => {
+                    parent.spawn(widget::button_medium(
+                        "Continue",
+                        move |_: On<Pointer<Click>>, mut commands: Commands| {
+                            commands.trigger(LoadGame { slot });
+                        },
+                    ));
+                }
+                SlotState::Unreadable => {}
+            }
+            if !matches!(state, SlotState::Empty) {
+                parent.spawn(widget::button_medium(
+                    "Delete",
+                    move |_: On<Pointer<Click>>,
+                          mut slot_to_delete: ResMut<SlotToDelete>,
+                          mut next_menu: ResMut<NextState<Menu>>| {
+                        slot_to_delete.0 = slot;
+                        next_menu.set(Menu::DeleteSave);
+                    },
+                ));
+            }
+        })),
+    )
+}
+
+/// A readable name for a map file, e.g. `Sample Cave` for `sample-cave.tmx`.
+fn location_name(map: Option<&str>) -> String {
+    let Some(map) = map else {
+        return "Unknown location".to_owned();
+    };
+    map.strip_suffix(".tmx")
+        .unwrap_or(map)
+        .split(['-', '_'])
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_play_time(secs: f32) -> String {
+    let minutes = (secs / 60.0) as u32;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time, e.g.
+/// `2024-03-01 18:30 UTC`.
+fn format_timestamp(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs_of_day = unix_secs % 86_400;
+
+    // Convert days since the epoch to a civil date, see
+    // <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year}-{month:02}-{day:02} {:02}:{:02} UTC",
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60
+    )
+}
+
+fn spawn_delete_save_menu(mut commands: Commands, slot_to_delete: Res<SlotToDelete>) {
+    commands.spawn((
+        widget::ui_root("Delete Save Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::DeleteSave),
+        children![
+            widget::header(format!("Delete slot {}?", slot_to_delete.0 + 1)),
+            widget::label("This can't be undone."),
+            widget::button("Delete", confirm_delete),
+            widget::button("Cancel", cancel_delete_on_click),
+        ],
+    ));
+}
+
+fn confirm_delete(
+    _: On<Pointer<Click>>,
+    slot_to_delete: Res<SlotToDelete>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if let Err(error) = SaveData::delete(slot_to_delete.0) {
+        error!("Failed to delete save slot {}: {error}", slot_to_delete.0);
+    }
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn cancel_delete_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn cancel_delete(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
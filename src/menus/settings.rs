@@ -2,9 +2,9 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{menus::Menu, screens::Screen, settings::Settings, theme::prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
@@ -15,24 +15,31 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            apply_volume_sliders,
+            update_volume_labels,
+            update_fullscreen_label,
+        )
+            .chain()
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
-fn spawn_settings_menu(mut commands: Commands) {
+fn spawn_settings_menu(mut commands: Commands, settings: Res<Settings>) {
     commands.spawn((
         widget::ui_root("Settings Menu"),
         GlobalZIndex(2),
         DespawnOnExit(Menu::Settings),
         children![
             widget::header("Settings"),
-            settings_grid(),
+            settings_grid(&settings),
             widget::button("Back", go_back_on_click),
         ],
     ));
 }
 
-fn settings_grid() -> impl Bundle {
+fn settings_grid(settings: &Settings) -> impl Bundle {
+    let volumes = VolumeChannel::ALL.map(|channel| (channel, channel.volume(settings)));
     (
         Name::new("Settings Grid"),
         Node {
@@ -42,65 +49,169 @@ fn settings_grid() -> impl Bundle {
             grid_template_columns: RepeatedGridTrack::px(2, 400.0),
             ..default()
         },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for (channel, volume) in volumes {
+                parent.spawn(setting_label(channel.label()));
+                parent.spawn(volume_widget(channel, volume));
+            }
+            parent.spawn(setting_label("Fullscreen"));
+            parent.spawn(fullscreen_widget());
+        })),
+    )
+}
+
+fn setting_label(text: &'static str) -> impl Bundle {
+    (
+        widget::label(text),
+        Node {
+            justify_self: JustifySelf::End,
+            ..default()
+        },
+    )
+}
+
+/// A volume setting that can be changed in the menu.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+enum VolumeChannel {
+    Master,
+    Music,
+    SoundEffects,
+    Ui,
+    Ambient,
+}
+
+impl VolumeChannel {
+    const ALL: [Self; 5] = [
+        Self::Master,
+        Self::Music,
+        Self::SoundEffects,
+        Self::Ui,
+        Self::Ambient,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Master => "Master Volume",
+            Self::Music => "Music",
+            Self::SoundEffects => "Sound Effects",
+            Self::Ui => "Interface",
+            Self::Ambient => "Ambience",
+        }
+    }
+
+    /// The loudest the channel can be set to. The master volume can boost
+    /// everything, the categories can only make their sounds quieter.
+    fn max_volume(self) -> f32 {
+        match self {
+            Self::Master => 3.0,
+            _ => 1.0,
+        }
+    }
+
+    fn volume(self, settings: &Settings) -> f32 {
+        match self {
+            Self::Master => settings.master_volume,
+            Self::Music => settings.music_volume,
+            Self::SoundEffects => settings.sound_effect_volume,
+            Self::Ui => settings.ui_volume,
+            Self::Ambient => settings.ambient_volume,
+        }
+    }
+
+    fn volume_mut(self, settings: &mut Settings) -> &mut f32 {
+        match self {
+            Self::Master => &mut settings.master_volume,
+            Self::Music => &mut settings.music_volume,
+            Self::SoundEffects => &mut settings.sound_effect_volume,
+            Self::Ui => &mut settings.ui_volume,
+            Self::Ambient => &mut settings.ambient_volume,
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VolumeSlider(VolumeChannel);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VolumeLabel(VolumeChannel);
+
+fn volume_widget(channel: VolumeChannel, volume: f32) -> impl Bundle {
+    (
+        Name::new("Volume Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            align_items: AlignItems::Center,
+            column_gap: px(10),
+            ..default()
+        },
         children![
             (
-                widget::label("Master Volume"),
-                Node {
-                    justify_self: JustifySelf::End,
-                    ..default()
-                }
+                widget::slider(volume / channel.max_volume()),
+                VolumeSlider(channel)
             ),
-            global_volume_widget(),
+            (widget::label(""), VolumeLabel(channel)),
         ],
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
+fn apply_volume_sliders(
+    sliders: Query<(&VolumeSlider, Ref<Slider>)>,
+    mut settings: ResMut<Settings>,
+) {
+    for (volume_slider, slider) in &sliders {
+        // The slider started out at the current volume.
+        if slider.is_added() || !slider.is_changed() {
+            continue;
+        }
+        let channel = volume_slider.0;
+        *channel.volume_mut(&mut settings) = slider.value * channel.max_volume();
+    }
+}
+
+fn update_volume_labels(settings: Res<Settings>, mut labels: Query<(&VolumeLabel, &mut Text)>) {
+    for (label, mut text) in &mut labels {
+        let percent = 100.0 * label.0.volume(&settings);
+        text.0 = format!("{percent:3.0}%");
+    }
+}
+
+fn fullscreen_widget() -> impl Bundle {
     (
-        Name::new("Global Volume Widget"),
+        Name::new("Fullscreen Widget"),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
         children![
-            widget::button_small("-", lower_global_volume),
+            widget::button_small("x", toggle_fullscreen),
             (
-                Name::new("Current Volume"),
+                Name::new("Current Fullscreen"),
                 Node {
                     padding: UiRect::horizontal(px(10)),
                     justify_content: JustifyContent::Center,
                     ..default()
                 },
-                children![(widget::label(""), GlobalVolumeLabel)],
+                children![(widget::label(""), FullscreenLabel)],
             ),
-            widget::button_small("+", raise_global_volume),
         ],
     )
 }
 
-const MIN_VOLUME: f32 = 0.0;
-const MAX_VOLUME: f32 = 3.0;
-
-fn lower_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
-}
-
-fn raise_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn toggle_fullscreen(_: On<Pointer<Click>>, mut settings: ResMut<Settings>) {
+    settings.fullscreen = !settings.fullscreen;
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+struct FullscreenLabel;
 
-fn update_global_volume_label(
-    global_volume: Res<GlobalVolume>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
+fn update_fullscreen_label(
+    settings: Res<Settings>,
+    mut label: Single<&mut Text, With<FullscreenLabel>>,
 ) {
-    let percent = 100.0 * global_volume.volume.to_linear();
-    label.0 = format!("{percent:3.0}%");
+    label.0 = if settings.fullscreen { "On" } else { "Off" }.to_owned();
 }
 
 fn go_back_on_click(
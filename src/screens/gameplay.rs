@@ -2,31 +2,56 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{Pause, demo::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    demo::{dialogue::ActiveDialogue, level::spawn_level, player::player_death_finished},
+    menus::Menu,
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
 
-    // Toggle pause on key press.
+    // Toggle pause on key press, except during conversations, which are
+    // already paused and would keep the overlay around after the menu closes.
     app.add_systems(
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
                 in_state(Screen::Gameplay)
                     .and(in_state(Menu::None))
+                    .and(not(resource_exists::<ActiveDialogue>))
                     .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
             ),
+            (pause, spawn_pause_overlay, open_inventory_menu).run_if(
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::None))
+                    .and(not(resource_exists::<ActiveDialogue>))
+                    .and(input_just_pressed(KeyCode::KeyI)),
+            ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
+                    .and(not(in_state(Menu::GameOver)))
                     .and(input_just_pressed(KeyCode::KeyP)),
             ),
         ),
     );
+
+    // Show the game over menu once the player has finished dying.
+    app.add_systems(
+        Update,
+        (pause, spawn_pause_overlay, open_game_over_menu).run_if(
+            in_state(Screen::Gameplay)
+                .and(in_state(Menu::None))
+                .and(player_death_finished),
+        ),
+    );
     app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause));
+    // Conversations keep the game paused until they're over.
     app.add_systems(
         OnEnter(Menu::None),
-        unpause.run_if(in_state(Screen::Gameplay)),
+        unpause.run_if(in_state(Screen::Gameplay).and(not(resource_exists::<ActiveDialogue>))),
     );
 }
 
@@ -56,6 +81,14 @@ fn open_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Pause);
 }
 
+fn open_inventory_menu(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Inventory);
+}
+
+fn open_game_over_menu(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::GameOver);
+}
+
 fn close_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
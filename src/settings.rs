@@ -0,0 +1,170 @@
+//! Settings that persist across runs, such as the volume.
+//!
+//! [`Settings`] are read from `settings.ron` in the platform's config
+//! directory (see [`storage`](crate::storage)) before Bevy's plugins are added,
+//! so that audio and the window start out with them. Any change to the
+//! resource is applied right away, and written back once it settles.
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::CategoryVolumes,
+    storage::{self, StorageDir},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Settings>()
+        .init_resource::<PendingWrite>();
+
+    app.add_systems(
+        Update,
+        (
+            (apply_settings, schedule_write)
+                .run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+            write_settings,
+        )
+            .chain(),
+    );
+}
+
+/// The version of the settings format. Bump this whenever a change to it
+/// would break reading older settings.
+const SETTINGS_VERSION: u32 = 1;
+
+const SETTINGS_PATH: &str = "settings.ron";
+
+/// How long the settings have to stay the same before they're written, so that
+/// dragging a slider doesn't write them every frame.
+const WRITE_DELAY_SECS: f32 = 0.5;
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+// Settings that are missing from the file, e.g. because they were added in a
+// later version of the game, keep their defaults.
+#[serde(default)]
+pub struct Settings {
+    version: u32,
+    /// The linear volume of all audio.
+    pub master_volume: f32,
+    /// The linear volume of each audio category, on top of the master volume.
+    pub music_volume: f32,
+    pub sound_effect_volume: f32,
+    pub ui_volume: f32,
+    pub ambient_volume: f32,
+    pub fullscreen: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sound_effect_volume: 1.0,
+            ui_volume: 1.0,
+            ambient_volume: 1.0,
+            fullscreen: false,
+        }
+    }
+}
+
+/// Just enough of the settings to find out what version they are.
+#[derive(Deserialize)]
+struct SettingsHeader {
+    version: u32,
+}
+
+impl Settings {
+    /// Reads the saved settings, or the defaults if nothing was saved yet.
+    pub fn read() -> Result<Self, BevyError> {
+        let Some(contents) = storage::read(StorageDir::Config, SETTINGS_PATH)? else {
+            return Ok(Self::default());
+        };
+        let header: SettingsHeader = ron::from_str(&contents)?;
+        if header.version > SETTINGS_VERSION {
+            return Err(BevyError::from(format!(
+                "settings version {} is newer than version {SETTINGS_VERSION}",
+                header.version
+            )));
+        }
+        Ok(ron::from_str(&contents)?)
+    }
+
+    fn write(&self) -> Result<(), BevyError> {
+        let settings = Self {
+            version: SETTINGS_VERSION,
+            ..self.clone()
+        };
+        let contents = ron::ser::to_string_pretty(&settings, PrettyConfig::default())?;
+        storage::write(StorageDir::Config, SETTINGS_PATH, &contents)
+    }
+
+    pub fn global_volume(&self) -> GlobalVolume {
+        GlobalVolume::new(Volume::Linear(self.master_volume))
+    }
+
+    pub fn category_volumes(&self) -> CategoryVolumes {
+        CategoryVolumes {
+            music: Volume::Linear(self.music_volume),
+            sound_effects: Volume::Linear(self.sound_effect_volume),
+            ui: Volume::Linear(self.ui_volume),
+            ambient: Volume::Linear(self.ambient_volume),
+        }
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        } else {
+            WindowMode::Windowed
+        }
+    }
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut category_volumes: ResMut<CategoryVolumes>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    let volume = settings.global_volume().volume;
+    if global_volume.volume != volume {
+        global_volume.volume = volume;
+    }
+    category_volumes.set_if_neq(settings.category_volumes());
+    let mode = settings.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
+
+/// Counts down to writing the settings after they changed.
+#[derive(Resource, Default)]
+struct PendingWrite(Option<Timer>);
+
+fn schedule_write(mut pending_write: ResMut<PendingWrite>) {
+    pending_write.0 = Some(Timer::from_seconds(WRITE_DELAY_SECS, TimerMode::Once));
+}
+
+fn write_settings(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut pending_write: ResMut<PendingWrite>,
+) {
+    let Some(timer) = &mut pending_write.0 else {
+        return;
+    };
+    if !timer.tick(time.delta()).is_finished() {
+        return;
+    }
+    pending_write.0 = None;
+    if let Err(error) = settings.write() {
+        error!("Failed to save the settings: {error}");
+    }
+}
@@ -0,0 +1,120 @@
+//! Small text files that outlive the game, such as save games.
+//!
+//! On native, files live in the platform's data or config directory, e.g.
+//! `~/.local/share/eager-omega` on Linux. On the web, they live in the
+//! browser's local storage, keyed by their path.
+
+use bevy::prelude::*;
+
+/// The directory inside the platform directories that the game's files go in.
+const APP_DIR: &str = "eager-omega";
+
+/// Where a file is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageDir {
+    /// For settings.
+    Config,
+    /// For everything else, such as save games.
+    Data,
+}
+
+/// Reads the file at `path`, or `None` if there is none.
+pub fn read(dir: StorageDir, path: &str) -> Result<Option<String>, BevyError> {
+    platform::read(dir, path)
+}
+
+/// Writes `contents` to the file at `path`, replacing what was there.
+pub fn write(dir: StorageDir, path: &str, contents: &str) -> Result<(), BevyError> {
+    platform::write(dir, path, contents)
+}
+
+/// Removes the file at `path`, if there is one.
+pub fn remove(dir: StorageDir, path: &str) -> Result<(), BevyError> {
+    platform::remove(dir, path)
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod platform {
+    use std::{fs, io, path::PathBuf};
+
+    use bevy::prelude::*;
+
+    use super::{APP_DIR, StorageDir};
+
+    fn full_path(dir: StorageDir, path: &str) -> Result<PathBuf, BevyError> {
+        let base = match dir {
+            StorageDir::Config => dirs::config_dir(),
+            StorageDir::Data => dirs::data_dir(),
+        };
+        let base = base.ok_or_else(|| BevyError::from(format!("no {dir:?} directory")))?;
+        Ok(base.join(APP_DIR).join(path))
+    }
+
+    pub fn read(dir: StorageDir, path: &str) -> Result<Option<String>, BevyError> {
+        match fs::read_to_string(full_path(dir, path)?) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn write(dir: StorageDir, path: &str, contents: &str) -> Result<(), BevyError> {
+        let path = full_path(dir, path)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first, so a crash halfway through
+        // doesn't leave a broken file behind.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    pub fn remove(dir: StorageDir, path: &str) -> Result<(), BevyError> {
+        match fs::remove_file(full_path(dir, path)?) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod platform {
+    use bevy::prelude::*;
+    use web_sys::Storage;
+
+    use super::{APP_DIR, StorageDir};
+
+    fn local_storage() -> Result<Storage, BevyError> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| BevyError::from("local storage isn't available"))
+    }
+
+    fn key(dir: StorageDir, path: &str) -> String {
+        let dir = match dir {
+            StorageDir::Config => "config",
+            StorageDir::Data => "data",
+        };
+        format!("{APP_DIR}/{dir}/{path}")
+    }
+
+    pub fn read(dir: StorageDir, path: &str) -> Result<Option<String>, BevyError> {
+        local_storage()?
+            .get_item(&key(dir, path))
+            .map_err(|error| BevyError::from(format!("{error:?}")))
+    }
+
+    pub fn write(dir: StorageDir, path: &str, contents: &str) -> Result<(), BevyError> {
+        local_storage()?
+            .set_item(&key(dir, path), contents)
+            .map_err(|error| BevyError::from(format!("{error:?}")))
+    }
+
+    pub fn remove(dir: StorageDir, path: &str) -> Result<(), BevyError> {
+        local_storage()?
+            .remove_item(&key(dir, path))
+            .map_err(|error| BevyError::from(format!("{error:?}")))
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{asset_tracking::LoadResource, audio::sound_effect};
+use crate::{asset_tracking::LoadResource, audio::ui_sound};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_observer(apply_interaction_palette_on_click);
@@ -80,7 +80,7 @@ fn play_sound_effect_on_click(
     interaction_assets: If<Res<InteractionAssets>>,
     mut commands: Commands,
 ) {
-    commands.spawn(sound_effect(interaction_assets.click.clone()));
+    commands.spawn(ui_sound(interaction_assets.click.clone()));
 }
 
 fn play_sound_effect_on_over(
@@ -88,5 +88,5 @@ fn play_sound_effect_on_over(
     interaction_assets: If<Res<InteractionAssets>>,
     mut commands: Commands,
 ) {
-    commands.spawn(sound_effect(interaction_assets.hover.clone()));
+    commands.spawn(ui_sound(interaction_assets.hover.clone()));
 }
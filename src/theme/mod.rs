@@ -5,15 +5,18 @@
 
 pub mod interaction;
 pub mod palette;
+pub mod slider;
 pub mod widget;
 
 #[allow(unused_imports)]
 pub mod prelude {
-    pub use super::{interaction::InteractionPalette, palette as ui_palette, widget};
+    pub use super::{
+        interaction::InteractionPalette, palette as ui_palette, slider::Slider, widget,
+    };
 }
 
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((interaction::plugin, slider::plugin));
 }
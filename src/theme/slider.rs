@@ -0,0 +1,63 @@
+//! A horizontal slider widget. See [`widget::slider`](super::widget::slider).
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Slider>();
+    app.add_observer(set_slider_on_press);
+    app.add_observer(set_slider_on_drag);
+    app.add_systems(Update, update_slider_fill);
+}
+
+/// The value of a slider, from 0 at the left end to 1 at the right end.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Slider {
+    pub value: f32,
+}
+
+/// The filled part of a slider, left of its value.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(super) struct SliderFill;
+
+/// The value of the slider at the pointer's horizontal position.
+fn value_at(pointer: Vec2, node: &ComputedNode, transform: &UiGlobalTransform) -> f32 {
+    let width = node.size().x * node.inverse_scale_factor();
+    let left = transform.translation.x * node.inverse_scale_factor() - width / 2.0;
+    ((pointer.x - left) / width).clamp(0.0, 1.0)
+}
+
+fn set_slider_on_press(
+    press: On<Pointer<Press>>,
+    mut sliders: Query<(&mut Slider, &ComputedNode, &UiGlobalTransform)>,
+) {
+    if let Ok((mut slider, node, transform)) = sliders.get_mut(press.event_target()) {
+        slider.set_if_neq(Slider {
+            value: value_at(press.pointer_location.position, node, transform),
+        });
+    }
+}
+
+fn set_slider_on_drag(
+    drag: On<Pointer<Drag>>,
+    mut sliders: Query<(&mut Slider, &ComputedNode, &UiGlobalTransform)>,
+) {
+    if let Ok((mut slider, node, transform)) = sliders.get_mut(drag.event_target()) {
+        slider.set_if_neq(Slider {
+            value: value_at(drag.pointer_location.position, node, transform),
+        });
+    }
+}
+
+fn update_slider_fill(
+    sliders: Query<(&Slider, &Children), Changed<Slider>>,
+    mut fills: Query<&mut Node, With<SliderFill>>,
+) {
+    for (slider, children) in &sliders {
+        let mut fills = fills.iter_many_mut(children);
+        while let Some(mut node) = fills.fetch_next() {
+            node.width = percent(slider.value * 100.0);
+        }
+    }
+}
@@ -7,7 +7,11 @@ use bevy::{
     prelude::*,
 };
 
-use crate::theme::{interaction::InteractionPalette, palette::*};
+use crate::theme::{
+    interaction::InteractionPalette,
+    palette::*,
+    slider::{Slider, SliderFill},
+};
 
 /// A root UI node that fills the window and centers its content.
 pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
@@ -68,6 +72,27 @@ where
     )
 }
 
+/// A medium-sized button with text and an action defined as an [`Observer`].
+/// Fits in rows of several buttons.
+pub fn button_medium<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        text,
+        action,
+        Node {
+            width: px(200),
+            height: px(60),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    )
+}
+
 /// A small square button with text and an action defined as an [`Observer`].
 pub fn button_small<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
@@ -88,6 +113,33 @@ where
     )
 }
 
+/// A horizontal slider starting out at `value`, from 0 to 1. Click or drag it
+/// to change its [`Slider`] value.
+pub fn slider(value: f32) -> impl Bundle {
+    (
+        Name::new("Slider"),
+        Slider { value },
+        Node {
+            width: px(240),
+            height: px(24),
+            ..default()
+        },
+        BackgroundColor(BUTTON_PRESSED_BACKGROUND),
+        children![(
+            Name::new("Slider Fill"),
+            SliderFill,
+            Node {
+                width: percent(value * 100.0),
+                height: percent(100),
+                ..default()
+            },
+            BackgroundColor(BUTTON_HOVERED_BACKGROUND),
+            // Let the slider itself receive picking events.
+            Pickable::IGNORE,
+        )],
+    )
+}
+
 /// A simple button with text and an action defined as an [`Observer`]. The button's layout is provided by `button_bundle`.
 fn button_base<E, B, M, I>(
     text: impl Into<String>,